@@ -1,62 +1,99 @@
-use crate::Config;
-use crate::common_interface::BenchmarkFmIndex;
+use crate::common_interface::{BenchmarkFmIndex, Capabilities, FileIoSupport};
+use crate::error::BenchmarkError;
+use crate::input::Texts;
+use crate::registry::{LibraryInfo, Variant};
+use crate::{Alphabet, Config};
 
 use std::path::{Path, PathBuf};
-use std::str::FromStr;
 
 use awry::{alphabet, fm_index};
 
 // Applies many smart tricks. Writes diagnostics to stdout, which libraries usually don't do.
 pub type AwryFmIndex = awry::fm_index::FmIndex;
 
+pub const LIBRARY: LibraryInfo = LibraryInfo {
+    crate_name: "awry",
+    url: "https://github.com/UM-Applied-Algorithms-Lab/AWRY",
+    variants: &[Variant {
+        name: "awry",
+        display_name: "awry",
+        color: "grey",
+        color_with_threads: "grey",
+        capabilities: AwryFmIndex::CAPABILITIES,
+        run_benchmark: AwryFmIndex::run_benchmark,
+    }],
+};
+
 impl BenchmarkFmIndex for AwryFmIndex {
     type IndexRef<'a> = &'a Self;
+    type Cursor<'a> = ();
+
+    const CAPABILITIES: Capabilities = Capabilities {
+        multiple_texts: true,
+        // writes the index in many small unbuffered chunks, but is still fast enough for large inputs
+        file_io: FileIoSupport::Slow,
+        file_io_mmap: false,
+        // the suffix array construction of libsufr sorts the partitions with rayon, so it uses the global thread pool
+        // of the build thread count
+        multithreaded_construction: true,
+        locate: false,
+        cursor: false,
+        reads_texts_from_file: true,
+        alphabets: &[Alphabet::Dna, Alphabet::Protein],
+    };
 
-    fn construct_for_benchmark(config: &Config, _texts: Option<Vec<Vec<u8>>>) -> Self {
+    fn construct_for_benchmark(
+        config: &Config,
+        _texts: Option<Texts>,
+    ) -> Result<Self, BenchmarkError> {
+        let lookup_table_kmer_len = u8::try_from(config.lookup_table_depth()).map_err(|_| {
+            BenchmarkError::Unsupported(format!(
+                "awry doesn't support a lookup table depth of {}",
+                config.lookup_table_depth()
+            ))
+        })?;
+
+        // compressed input files are also decompressed by awry (via needletail)
         let build_args = fm_index::FmBuildArgs {
             input_file_src: config.input_texts.get_filepath(),
-            suffix_array_output_src: Some(
-                PathBuf::from_str("indices/awry_temporary_suffix_array_output.txt").unwrap(),
-            ),
+            suffix_array_output_src: Some(PathBuf::from(
+                "indices/awry_temporary_suffix_array_output.txt",
+            )),
             suffix_array_compression_ratio: Some(config.suffix_array_sampling_rate as u64),
-            lookup_table_kmer_len: Some(config.depth_of_lookup_table as u8),
-            alphabet: alphabet::SymbolAlphabet::Nucleotide,
+            lookup_table_kmer_len: Some(lookup_table_kmer_len),
+            alphabet: match config.alphabet() {
+                Alphabet::Dna => alphabet::SymbolAlphabet::Nucleotide,
+                Alphabet::Protein => alphabet::SymbolAlphabet::Amino,
+                // excluded by the capabilities
+                Alphabet::Bytes => unreachable!(),
+            },
             // for now, awry doesn't get the max_query_len advantage, because it would make the whole benchmark setup more complicated
             // as different indiced would have to be stored for different query lengths
             max_query_len: None,
             remove_intermediate_suffix_array_file: true,
         };
 
-        fm_index::FmIndex::new(&build_args).unwrap()
+        fm_index::FmIndex::new(&build_args).map_err(BenchmarkError::construction)
     }
 
-    fn supports_file_io_for_benchmark(_config: &Config) -> bool {
-        true
+    fn write_to_file_for_benchmark(self, path: &Path) -> Result<(), BenchmarkError> {
+        self.save(path).map_err(|e| BenchmarkError::io(path, e))
     }
 
-    fn write_to_file_for_benchmark(self, path: &Path) {
-        self.save(path).unwrap();
+    fn load_from_file_for_benchmark(path: &Path) -> Result<Self, BenchmarkError> {
+        Self::load(path).map_err(|e| BenchmarkError::io(path, e))
     }
 
-    fn load_from_file_for_benchmark(path: &Path) -> Self {
-        Self::load(path).unwrap()
-    }
-
-    fn as_stub_for_benchmark<'a>(&'a self) -> Self::IndexRef<'a> {
-        self
+    fn as_stub_for_benchmark<'a>(&'a self) -> Result<Self::IndexRef<'a>, BenchmarkError> {
+        Ok(self)
     }
 
+    // the indexed symbols are ASCII, so a query that is not valid UTF-8 has no occurrences
     fn count_for_benchmark<'a>(index: &Self::IndexRef<'a>, query: &[u8]) -> usize {
-        let query = str::from_utf8(query).unwrap();
-        index.count_string(query) as usize
+        str::from_utf8(query).map_or(0, |query| index.count_string(query) as usize)
     }
 
     fn count_via_locate_for_benchmark<'a>(index: &Self::IndexRef<'a>, query: &[u8]) -> usize {
-        let query = str::from_utf8(query).unwrap();
-        index.locate_string(query).len()
-    }
-
-    fn supports_locate_for_benchmark() -> bool {
-        false
+        str::from_utf8(query).map_or(0, |query| index.locate_string(query).len())
     }
 }
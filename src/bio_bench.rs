@@ -1,12 +1,15 @@
-use crate::Config;
-use crate::common_interface::BenchmarkFmIndex;
+use crate::common_interface::{BenchmarkFmIndex, Capabilities, FileIoSupport, HitPosition};
+use crate::error::BenchmarkError;
+use crate::input::Texts;
+use crate::registry::{LibraryInfo, Variant};
+use crate::{Alphabet, Config};
 
 use std::fs::File;
 use std::path::Path;
 
 use bio::alphabets;
 use bio::data_structures::bwt::{self, Occ};
-use bio::data_structures::fmindex::{BackwardSearchResult, FMIndex, FMIndexable};
+use bio::data_structures::fmindex::{BackwardSearchResult, FMIndex, FMIndexable, Interval};
 use bio::data_structures::suffix_array::{self, SampledSuffixArray, SuffixArray};
 
 // Large package of many algorithms and data structures. The API is the most complicated one,
@@ -15,14 +18,55 @@ pub struct BioFmIndex<const R: usize> {
     inner: SampledSuffixArray<Vec<u8>, Vec<usize>, Occ>,
 }
 
+pub const LIBRARY: LibraryInfo = LibraryInfo {
+    crate_name: "rust-bio",
+    url: "https://github.com/rust-bio/rust-bio",
+    variants: &[
+        Variant {
+            name: "bio-small",
+            display_name: "bio small",
+            color: "limegreen",
+            color_with_threads: "limegreen",
+            capabilities: BioFmIndex::<2048>::CAPABILITIES,
+            run_benchmark: BioFmIndex::<2048>::run_benchmark,
+        },
+        Variant {
+            name: "bio-large",
+            display_name: "bio large",
+            color: "forestgreen",
+            color_with_threads: "forestgreen",
+            capabilities: BioFmIndex::<32>::CAPABILITIES,
+            run_benchmark: BioFmIndex::<32>::run_benchmark,
+        },
+    ],
+};
+
 impl<const R: usize> BenchmarkFmIndex for BioFmIndex<R> {
     type IndexRef<'a> = &'a SampledSuffixArray<Vec<u8>, Vec<usize>, Occ>;
+    // half open, unlike the intervals during the backward search of the library
+    type Cursor<'a> = Interval;
+
+    const CAPABILITIES: Capabilities = Capabilities {
+        multiple_texts: false,
+        // the bio IO is super slow due to serde usage, I couldn't find a fast (de)serializer
+        file_io: FileIoSupport::VerySlow,
+        file_io_mmap: false,
+        multithreaded_construction: false,
+        locate: true,
+        cursor: true,
+        reads_texts_from_file: false,
+        alphabets: &[Alphabet::Dna, Alphabet::Protein, Alphabet::Bytes],
+    };
 
-    fn construct_for_benchmark(config: &Config, texts: Option<Vec<Vec<u8>>>) -> Self {
-        let mut text: Vec<_> = texts.unwrap().into_iter().flatten().collect();
-        text.push(b'$');
+    fn construct_for_benchmark(
+        config: &Config,
+        texts: Option<Texts>,
+    ) -> Result<Self, BenchmarkError> {
+        let mut text = texts.unwrap().into_symbols();
+        // the sentinel has to be smaller than all other symbols, the zero byte is not part of any alphabet
+        text.push(0);
 
-        let alphabet = alphabets::Alphabet::new(b"$ACGTN");
+        let alphabet = alphabets::Alphabet::new([0].iter().chain(config.alphabet().symbols()));
 
         // I'm unsure about rank transformation
 
@@ -39,39 +83,33 @@ impl<const R: usize> BenchmarkFmIndex for BioFmIndex<R> {
         let less = bwt::less(&bwt, &alphabet);
         let occ = Occ::new(&bwt, occ_sampling_rate, &alphabet);
 
-        Self {
+        Ok(Self {
             inner: suffix_array.sample(&text, bwt, less, occ, config.suffix_array_sampling_rate),
-        }
+        })
     }
 
-    fn supports_file_io_for_benchmark(config: &Config) -> bool {
-        // the bio IO is super slow due to serde usage, I couldn't find a fast (de)serializer
-        match config.input_texts {
-            crate::InputTexts::Chromosome => true,
-            crate::InputTexts::I32 => true,
-            crate::InputTexts::Hg38 => false,
-            crate::InputTexts::DoubleHg38 => false,
-        }
-    }
-
-    fn write_to_file_for_benchmark(self, path: &Path) {
-        let mut file = File::create(path).unwrap();
+    fn write_to_file_for_benchmark(self, path: &Path) -> Result<(), BenchmarkError> {
+        let mut file = File::create(path).map_err(|e| BenchmarkError::io(path, e))?;
         let config = bincode::config::standard().with_fixed_int_encoding();
 
-        bincode::serde::encode_into_std_write(self.inner, &mut file, config).unwrap();
+        bincode::serde::encode_into_std_write(self.inner, &mut file, config)
+            .map_err(|e| BenchmarkError::io(path, e))?;
+
+        Ok(())
     }
 
-    fn load_from_file_for_benchmark(path: &Path) -> Self {
-        let mut file = File::open(path).unwrap();
+    fn load_from_file_for_benchmark(path: &Path) -> Result<Self, BenchmarkError> {
+        let mut file = File::open(path).map_err(|e| BenchmarkError::io(path, e))?;
         let config = bincode::config::standard().with_fixed_int_encoding();
 
-        Self {
-            inner: bincode::serde::decode_from_std_read(&mut file, config).unwrap(),
-        }
+        Ok(Self {
+            inner: bincode::serde::decode_from_std_read(&mut file, config)
+                .map_err(|e| BenchmarkError::io(path, e))?,
+        })
     }
 
-    fn as_stub_for_benchmark<'a>(&'a self) -> Self::IndexRef<'a> {
-        &self.inner
+    fn as_stub_for_benchmark<'a>(&'a self) -> Result<Self::IndexRef<'a>, BenchmarkError> {
+        Ok(&self.inner)
     }
 
     fn count_for_benchmark<'a>(index: &Self::IndexRef<'a>, query: &[u8]) -> usize {
@@ -97,4 +135,133 @@ impl<const R: usize> BenchmarkFmIndex for BioFmIndex<R> {
             BackwardSearchResult::Absent => 0,
         }
     }
+
+    fn locate_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+        hits: &mut Vec<HitPosition>,
+    ) {
+        let index_stub = FMIndex::new(index.bwt(), index.less(), index.occ());
+
+        if let BackwardSearchResult::Complete(interval) = index_stub.backward_search(query.iter()) {
+            hits.extend(interval.occ(*index).into_iter().map(|position| {
+                HitPosition::Concatenated {
+                    position,
+                    separator_len: 0,
+                }
+            }));
+        }
+    }
+
+    // the suffix array interval is located position by position
+    fn locate_first_k_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+        k: usize,
+    ) -> usize {
+        let index_stub = FMIndex::new(index.bwt(), index.less(), index.occ());
+
+        match index_stub.backward_search(query.iter()) {
+            BackwardSearchResult::Complete(interval) => (interval.lower..interval.upper)
+                .take(k)
+                .filter_map(|position| index.get(position))
+                .count(),
+            BackwardSearchResult::Partial(..) => 0,
+            BackwardSearchResult::Absent => 0,
+        }
+    }
+
+    // the partial result of the backward search of the library is exactly the longest matching suffix
+    fn longest_suffix_match_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+    ) -> (usize, usize) {
+        let index_stub = FMIndex::new(index.bwt(), index.less(), index.occ());
+
+        match index_stub.backward_search(query.iter()) {
+            BackwardSearchResult::Complete(interval) => {
+                (query.len(), interval.upper - interval.lower)
+            }
+            BackwardSearchResult::Partial(interval, length) => {
+                (length, interval.upper - interval.lower)
+            }
+            BackwardSearchResult::Absent => (0, 0),
+        }
+    }
+
+    fn cursor_for_benchmark<'a>(index: &Self::IndexRef<'a>) -> Self::Cursor<'a> {
+        Interval {
+            lower: 0,
+            upper: index.bwt().len(),
+        }
+    }
+
+    // the same steps as in the backward search of the library
+    fn extend_cursor_front_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+        symbol: u8,
+    ) -> Self::Cursor<'a> {
+        if cursor.lower == cursor.upper {
+            return *cursor;
+        }
+
+        let index_stub = FMIndex::new(index.bwt(), index.less(), index.occ());
+        let less = index_stub.less(symbol);
+
+        Interval {
+            lower: less
+                + if cursor.lower > 0 {
+                    index_stub.occ(cursor.lower - 1, symbol)
+                } else {
+                    0
+                },
+            upper: less + index_stub.occ(cursor.upper - 1, symbol),
+        }
+    }
+
+    // a partial match of the query is an empty interval
+    fn search_cursor_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+    ) -> Self::Cursor<'a> {
+        let index_stub = FMIndex::new(index.bwt(), index.less(), index.occ());
+
+        match index_stub.backward_search(query.iter()) {
+            BackwardSearchResult::Complete(interval) => interval,
+            BackwardSearchResult::Partial(..) | BackwardSearchResult::Absent => {
+                Interval { lower: 0, upper: 0 }
+            }
+        }
+    }
+
+    fn cursor_count_via_locate_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+    ) -> usize {
+        cursor.occ(*index).len()
+    }
+
+    fn cursor_count_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+    ) -> usize {
+        cursor.upper - cursor.lower
+    }
+
+    fn cursor_locate_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+        hits: &mut Vec<HitPosition>,
+    ) {
+        hits.extend(
+            cursor
+                .occ(*index)
+                .into_iter()
+                .map(|position| HitPosition::Concatenated {
+                    position,
+                    separator_len: 0,
+                }),
+        );
+    }
 }
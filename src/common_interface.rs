@@ -2,7 +2,15 @@ use std::path::Path;
 
 use log::info;
 
-use crate::{BenchmarkResult, Config, SearchMode};
+use crate::compression::Compression;
+use crate::error::BenchmarkError;
+use crate::hit_stats::HitStats;
+use crate::input::{DegenerateSymbolStats, Queries, QueryStream, Texts, read_queries, read_texts};
+use crate::soft_mask::{SoftMask, SoftMaskStats};
+use crate::{
+    Alphabet, BenchmarkResult, Config, DegeneratePolicy, EditPruning, InputTexts, QueryOrder,
+    SearchConfig, SearchMode,
+};
 
 pub trait BenchmarkFmIndex: Sized {
     // this interface is a bit complicated, because the sview fmindex is essentially a reference to a slice, but is
@@ -12,14 +20,22 @@ pub trait BenchmarkFmIndex: Sized {
     where
         Self: 'a;
 
-    fn as_stub_for_benchmark<'a>(&'a self) -> Self::IndexRef<'a>;
+    // the interval of the currently searched query during a backward search, () if the library has no cursor
+    type Cursor<'a>
+    where
+        Self: 'a;
+
+    fn as_stub_for_benchmark<'a>(&'a self) -> Result<Self::IndexRef<'a>, BenchmarkError>;
 
-    fn construct_for_benchmark(config: &Config, texts: Option<Vec<Vec<u8>>>) -> Self;
+    fn construct_for_benchmark(
+        config: &Config,
+        texts: Option<Texts>,
+    ) -> Result<Self, BenchmarkError>;
 
     fn count_for_benchmark<'a>(index: &Self::IndexRef<'a>, query: &[u8]) -> usize;
 
     // implement only if there is functionality that makes searching multiple queries more efficient
-    fn count_many_for_benchmark<'a>(index: &Self::IndexRef<'a>, queries: &[Vec<u8>]) -> usize {
+    fn count_many_for_benchmark<'a>(index: &Self::IndexRef<'a>, queries: &Queries) -> usize {
         queries
             .iter()
             .map(|q| Self::count_for_benchmark(index, q))
@@ -31,7 +47,7 @@ pub trait BenchmarkFmIndex: Sized {
     // implement only if there is functionality that makes searching multiple queries more efficient
     fn count_many_via_locate_for_benchmark<'a>(
         index: &Self::IndexRef<'a>,
-        queries: &[Vec<u8>],
+        queries: &Queries,
     ) -> usize {
         queries
             .iter()
@@ -39,121 +55,920 @@ pub trait BenchmarkFmIndex: Sized {
             .sum()
     }
 
+    // implement only if there is functionality that makes this more efficient than counting
+    fn exists_for_benchmark<'a>(index: &Self::IndexRef<'a>, query: &[u8]) -> bool {
+        Self::count_for_benchmark(index, query) > 0
+    }
+
+    // implement only if the library can locate lazily, returns the number of located occurrences
+    fn locate_first_k_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+        k: usize,
+    ) -> usize {
+        Self::count_via_locate_for_benchmark(index, query).min(k)
+    }
+
+    // what the library supports, used by the benchmark harness and for the feature comparison in the report
+    const CAPABILITIES: Capabilities;
+
     // implement only if the library supports file IO of the FM-Index
-    fn supports_file_io_for_benchmark(_config: &Config) -> bool {
-        false
+    fn write_to_file_for_benchmark(self, _path: &Path) -> Result<(), BenchmarkError> {
+        unreachable!()
     }
 
-    fn write_to_file_for_benchmark(self, _path: &Path) {
+    fn load_from_file_for_benchmark(_path: &Path) -> Result<Self, BenchmarkError> {
         unreachable!()
     }
 
-    fn load_from_file_for_benchmark(_path: &Path) -> Self {
+    // implement only if the library supports locate, used to relate the hits to the soft-masked regions
+    fn locate_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        _query: &[u8],
+        _hits: &mut Vec<HitPosition>,
+    ) {
         unreachable!()
     }
 
-    fn needs_texts() -> bool {
-        true
+    // implement only if the library exposes the steps of the backward search, used for the approximate search
+    fn cursor_for_benchmark<'a>(_index: &Self::IndexRef<'a>) -> Self::Cursor<'a> {
+        unreachable!()
     }
 
-    // only temporary, until awry's issue is fixed
-    fn supports_locate_for_benchmark() -> bool {
-        true
+    // prepends the symbol to the currently searched query
+    fn extend_cursor_front_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        _cursor: &Self::Cursor<'a>,
+        _symbol: u8,
+    ) -> Self::Cursor<'a> {
+        unreachable!()
+    }
+
+    fn cursor_count_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        _cursor: &Self::Cursor<'a>,
+    ) -> usize {
+        unreachable!()
+    }
+
+    fn cursor_locate_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        _cursor: &Self::Cursor<'a>,
+        _hits: &mut Vec<HitPosition>,
+    ) {
+        unreachable!()
+    }
+
+    // implement only if the library can search the whole query more efficiently than stepwise
+    fn search_cursor_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+    ) -> Self::Cursor<'a> {
+        let mut cursor = Self::cursor_for_benchmark(index);
+
+        for &symbol in query.iter().rev() {
+            cursor = Self::extend_cursor_front_for_benchmark(index, &cursor, symbol);
+        }
+
+        cursor
+    }
+
+    // implement only if the library can locate the occurrences of the cursor without collecting them
+    fn cursor_count_via_locate_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+    ) -> usize {
+        let mut hits = Vec::new();
+        Self::cursor_locate_for_benchmark(index, cursor, &mut hits);
+        hits.len()
+    }
+
+    // Returns the length of the longest suffix of the query that occurs in the texts and its number of occurrences.
+    // implement only if the library has a more efficient way than stepwise backward search or binary search
+    fn longest_suffix_match_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+    ) -> (usize, usize) {
+        if Self::CAPABILITIES.cursor {
+            let mut cursor = Self::cursor_for_benchmark(index);
+            let mut length = 0;
+            let mut count = 0;
+
+            for &symbol in query.iter().rev() {
+                let next = Self::extend_cursor_front_for_benchmark(index, &cursor, symbol);
+                let next_count = Self::cursor_count_for_benchmark(index, &next);
+
+                if next_count == 0 {
+                    break;
+                }
+
+                cursor = next;
+                length += 1;
+                count = next_count;
+            }
+
+            (length, count)
+        } else {
+            // if a suffix occurs, all shorter suffixes occur as well
+            let mut length = 0;
+            let mut max_length = query.len();
+            let mut count = 0;
+
+            while length < max_length {
+                let candidate_length = (length + max_length).div_ceil(2);
+                let candidate_count =
+                    Self::count_for_benchmark(index, &query[query.len() - candidate_length..]);
+
+                if candidate_count > 0 {
+                    length = candidate_length;
+                    count = candidate_count;
+                } else {
+                    max_length = candidate_length - 1;
+                }
+            }
+
+            (length, count)
+        }
     }
 
     // from here on: do not implement these methods
 
-    fn construct_or_load_for_benchmark(config: &Config) -> (Self, ConstructionMetrics) {
+    fn construct_or_load_for_benchmark(
+        config: &Config,
+    ) -> Result<(Self, ConstructionMetrics), BenchmarkError> {
         let index_filepath = config.index_filepath();
 
         let start = std::time::Instant::now();
+        let mut texts_size_mb = None;
+        let mut texts_degenerate_symbols = None;
+
         let (index, was_constructed) = if config.skip_build
-            && std::fs::exists(&index_filepath).unwrap()
-            && Self::supports_file_io_for_benchmark(config)
+            && index_filepath.exists()
+            && Self::CAPABILITIES.runs_file_io_for(config)
         {
-            (Self::load_from_file_for_benchmark(&index_filepath), false)
+            (Self::load_from_file_for_benchmark(&index_filepath)?, false)
         } else {
-            let texts = if Self::needs_texts() {
-                Some(read_texts(config))
-            } else {
+            let texts = if Self::CAPABILITIES.reads_texts_from_file {
+                if config.texts_degenerate_policy.is_some() {
+                    return Err(BenchmarkError::Unsupported(format!(
+                        "{} reads the texts by itself, so the degenerate symbol policy can't be applied",
+                        config.library
+                    )));
+                }
+
                 None
+            } else {
+                let (texts, degenerate_symbol_stats) = read_texts(config)?;
+                texts_size_mb = Some(texts.symbols().len() as f64 / 1_000_000.0);
+                texts_degenerate_symbols = Some(degenerate_symbol_stats);
+                Some(texts)
             };
 
-            (Self::construct_for_benchmark(config, texts), true)
+            (Self::construct_for_benchmark(config, texts)?, true)
         };
 
-        let metrics = collect_and_log_after_build_metrics(start, was_constructed);
+        let metrics = collect_and_log_after_build_metrics(
+            start,
+            was_constructed,
+            texts_size_mb,
+            texts_degenerate_symbols,
+        );
 
-        (index, metrics)
+        Ok((index, metrics))
     }
 
-    fn run_search_benchmark(&self, config: &Config) -> SearchMetrics {
-        let queries = read_queries(config);
+    // returns the metrics of all queries and, if the soft mask is used, for repeat-derived and unique queries
+    fn run_search_benchmark(
+        &self,
+        config: &Config,
+    ) -> Result<Vec<(String, SearchMetrics)>, BenchmarkError> {
+        let search_config = config.search_config();
+        let stub = self.as_stub_for_benchmark()?;
+
+        if let Some(chunk_size) = config.stream_queries {
+            let search_metrics = Self::time_streaming_search(&stub, chunk_size, config)?;
+            return Ok(vec![(search_config.to_string(), search_metrics)]);
+        }
 
-        let mut total_num_hits = 0;
-        let mut running_times_secs = Vec::new();
+        let (queries, degenerate_symbol_stats) = read_queries(config)?;
 
-        let stub = self.as_stub_for_benchmark();
+        let mut search_metrics = Self::time_search(&stub, &queries, config);
+        search_metrics.degenerate_queries = Some(degenerate_symbol_stats);
 
-        for _ in 0..config.repeat_search {
+        if config.hit_stats {
+            search_metrics.hit_stats = Some(Self::collect_hit_stats(&stub, &queries));
+        }
+
+        if !config.soft_mask {
+            return Ok(vec![(search_config.to_string(), search_metrics)]);
+        }
+
+        let soft_mask = SoftMask::read(config)?;
+        let (classified_queries, soft_mask_stats) =
+            Self::classify_queries_by_soft_mask(&stub, queries, &soft_mask, config);
+        search_metrics.soft_mask = Some(soft_mask_stats);
+
+        info!(
+            "Repeat-derived queries: {}",
+            classified_queries.repeats.len()
+        );
+        let repeat_search_metrics = Self::time_search(&stub, &classified_queries.repeats, config);
+
+        info!("Unique queries: {}", classified_queries.unique.len());
+        let unique_search_metrics = Self::time_search(&stub, &classified_queries.unique, config);
+
+        info!("Queries without hits: {}", classified_queries.no_hits.len());
+        let no_hit_search_metrics = Self::time_search(&stub, &classified_queries.no_hits, config);
+
+        Ok(vec![
+            (search_config.to_string(), search_metrics),
+            (format!("{search_config}-repeats"), repeat_search_metrics),
+            (format!("{search_config}-unique"), unique_search_metrics),
+            (format!("{search_config}-no-hits"), no_hit_search_metrics),
+        ])
+    }
+
+    // the reverse complements of the both strands mode are not included
+    fn collect_hit_stats<'a>(index: &Self::IndexRef<'a>, queries: &Queries) -> HitStats {
+        let supports_locate = Self::CAPABILITIES.locate;
+        let mut hit_stats = HitStats::new(supports_locate);
+
+        for query in queries.iter() {
             let start = std::time::Instant::now();
+            let num_hits = Self::count_for_benchmark(index, query);
+            let count_time = start.elapsed();
+
+            let locate_time = supports_locate.then(|| {
+                let start = std::time::Instant::now();
+                std::hint::black_box(Self::count_via_locate_for_benchmark(index, query));
+                start.elapsed()
+            });
+
+            hit_stats.add_query(query, num_hits, count_time, locate_time);
+        }
+
+        hit_stats.log();
+
+        hit_stats
+    }
+
+    fn classify_queries_by_soft_mask<'a>(
+        index: &Self::IndexRef<'a>,
+        queries: Queries,
+        soft_mask: &SoftMask,
+        config: &Config,
+    ) -> (SoftMaskClassifiedQueries, SoftMaskStats) {
+        let mut classified_queries = SoftMaskClassifiedQueries {
+            repeats: Queries::new(),
+            unique: Queries::new(),
+            no_hits: Queries::new(),
+        };
+        let mut num_masked_hits = 0;
+        let mut num_unmasked_hits = 0;
+        let mut hits = Vec::new();
+
+        // the hits of a query and its reverse complement are classified together
+        let mut reverse_queries = Queries::new();
+        if config.both_strands {
+            queries.reverse_complement_into(&mut reverse_queries);
+        }
+        let mut reverse_queries_iter = reverse_queries.iter();
+
+        for query in queries.iter() {
+            hits.clear();
+            Self::locate_for_benchmark(index, query, &mut hits);
+
+            if let Some(reverse_query) = reverse_queries_iter.next() {
+                Self::locate_for_benchmark(index, reverse_query, &mut hits);
+            }
+
+            let num_masked = hits.iter().filter(|&&hit| soft_mask.is_masked(hit)).count();
+            num_masked_hits += num_masked;
+            num_unmasked_hits += hits.len() - num_masked;
+
+            if hits.is_empty() {
+                classified_queries.no_hits.push(query);
+            } else if num_masked * 2 > hits.len() {
+                classified_queries.repeats.push(query);
+            } else {
+                classified_queries.unique.push(query);
+            }
+        }
+
+        info!(
+            "Hits in masked regions: {num_masked_hits}, in unmasked regions: {num_unmasked_hits}"
+        );
+
+        let stats = SoftMaskStats {
+            num_repeat_queries: classified_queries.repeats.len(),
+            num_unique_queries: classified_queries.unique.len(),
+            num_no_hit_queries: classified_queries.no_hits.len(),
+            num_masked_hits,
+            num_unmasked_hits,
+        };
+
+        (classified_queries, stats)
+    }
+
+    fn search_many<'a>(
+        index: &Self::IndexRef<'a>,
+        queries: &Queries,
+        config: &Config,
+    ) -> SearchCounts {
+        let num_forward_hits = match config.search_mode {
+            SearchMode::Count => Self::count_many_for_benchmark(index, queries),
+            SearchMode::Locate => Self::count_many_via_locate_for_benchmark(index, queries),
+            SearchMode::Exists => queries
+                .iter()
+                .filter(|query| Self::exists_for_benchmark(index, query))
+                .count(),
+            SearchMode::LocateFirstK => queries
+                .iter()
+                .map(|query| Self::locate_first_k_for_benchmark(index, query, config.first_k))
+                .sum(),
+            SearchMode::Mismatches | SearchMode::Edits => {
+                return Self::approximate_search_many(index, queries, config);
+            }
+            SearchMode::Smems => {
+                let mut counts = SearchCounts::default();
+
+                for query in queries.iter() {
+                    Self::count_smems(index, query, config.min_seed_length, &mut counts);
+                }
+
+                return counts;
+            }
+            SearchMode::TrieCount => return Self::trie_count_many(index, queries),
+            SearchMode::LongestSuffix => {
+                let mut counts = SearchCounts::default();
+
+                for query in queries.iter() {
+                    let (length, count) = Self::longest_suffix_match_for_benchmark(index, query);
+
+                    counts.num_forward_hits += count;
+                    counts.longest_suffix.total_suffix_length += length;
+                    if length == query.len() {
+                        counts.longest_suffix.num_complete_matches += 1;
+                    }
+                }
+
+                return counts;
+            }
+        };
+
+        SearchCounts {
+            num_forward_hits,
+            ..SearchCounts::default()
+        }
+    }
 
-            total_num_hits = match config.search_mode {
-                SearchMode::Count => Self::count_many_for_benchmark(&stub, &queries),
-                SearchMode::Locate => Self::count_many_via_locate_for_benchmark(&stub, &queries),
+    fn approximate_search_many<'a>(
+        index: &Self::IndexRef<'a>,
+        queries: &Queries,
+        config: &Config,
+    ) -> SearchCounts {
+        let mut search = ApproximateSearch {
+            symbols: config.alphabet().concrete_symbols(),
+            edit_pruning: config.edit_pruning,
+            hits: Vec::new(),
+            num_explored_intervals: 0,
+        };
+        let mut num_forward_hits = 0;
+
+        for query in queries.iter() {
+            search.hits.clear();
+            let cursor = Self::cursor_for_benchmark(index);
+
+            match config.search_mode {
+                SearchMode::Mismatches => {
+                    Self::locate_with_mismatches(
+                        index,
+                        &cursor,
+                        query,
+                        config.max_errors,
+                        &mut search,
+                    );
+                }
+                _ => Self::locate_with_edits(
+                    index,
+                    &cursor,
+                    query,
+                    config.max_errors,
+                    EditOperation::Start,
+                    &mut search,
+                ),
+            }
+
+            num_forward_hits += search.hits.len();
+        }
+
+        SearchCounts {
+            num_forward_hits,
+            num_explored_intervals: search.num_explored_intervals,
+            ..SearchCounts::default()
+        }
+    }
+
+    // Backtracking over the substitutions, from the end of the query to the front. Different substitutions
+    // lead to disjoint intervals, so every hit is reported exactly once.
+    fn locate_with_mismatches<'a>(
+        index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+        remaining_query: &[u8],
+        max_mismatches: usize,
+        search: &mut ApproximateSearch,
+    ) {
+        if Self::cursor_count_for_benchmark(index, cursor) == 0 {
+            return;
+        }
+
+        let Some((&query_symbol, rest)) = remaining_query.split_last() else {
+            Self::cursor_locate_for_benchmark(index, cursor, &mut search.hits);
+            return;
+        };
+
+        if max_mismatches == 0 {
+            let next = Self::extend_cursor_front_for_benchmark(index, cursor, query_symbol);
+            search.num_explored_intervals += 1;
+            Self::locate_with_mismatches(index, &next, rest, 0, search);
+            return;
+        }
+
+        for &symbol in search.symbols {
+            let next = Self::extend_cursor_front_for_benchmark(index, cursor, symbol);
+            search.num_explored_intervals += 1;
+            let remaining_mismatches = if symbol == query_symbol {
+                max_mismatches
+            } else {
+                max_mismatches - 1
             };
 
-            running_times_secs.push(start.elapsed().as_millis() as f64 / 1_000.0);
+            Self::locate_with_mismatches(index, &next, rest, remaining_mismatches, search);
         }
+    }
 
-        let &min_time_secs = running_times_secs
-            .iter()
-            .min_by(|a, b| a.total_cmp(b))
-            .unwrap();
-        let avg_time_secs = running_times_secs.iter().sum::<f64>() / config.repeat_search as f64;
+    // Like the mismatch search, but the query symbol can also be skipped (insertion in the query) or a text symbol
+    // can be added without consuming a query symbol (deletion from the query). Unlike for mismatches, the same
+    // occurrence can be found multiple times, which is reduced by the pruning.
+    fn locate_with_edits<'a>(
+        index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+        remaining_query: &[u8],
+        max_edits: usize,
+        previous: EditOperation,
+        search: &mut ApproximateSearch,
+    ) {
+        if max_edits == 0 {
+            Self::locate_with_mismatches(index, cursor, remaining_query, 0, search);
+            return;
+        }
+
+        if Self::cursor_count_for_benchmark(index, cursor) == 0 {
+            return;
+        }
+
+        let Some((&query_symbol, rest)) = remaining_query.split_last() else {
+            Self::cursor_locate_for_benchmark(index, cursor, &mut search.hits);
+            return;
+        };
+
+        for &symbol in search.symbols {
+            let next = Self::extend_cursor_front_for_benchmark(index, cursor, symbol);
+            search.num_explored_intervals += 1;
+
+            if symbol == query_symbol {
+                Self::locate_with_edits(
+                    index,
+                    &next,
+                    rest,
+                    max_edits,
+                    EditOperation::Match,
+                    search,
+                );
+            } else {
+                Self::locate_with_edits(
+                    index,
+                    &next,
+                    rest,
+                    max_edits - 1,
+                    EditOperation::Substitution,
+                    search,
+                );
+            }
+        }
+
+        let canonical = search.edit_pruning == EditPruning::Canonical;
+        let indels_allowed = !canonical || previous != EditOperation::Start;
+
+        if indels_allowed
+            && !(canonical && (previous == EditOperation::Deletion || rest.is_empty()))
+        {
+            Self::locate_with_edits(
+                index,
+                cursor,
+                rest,
+                max_edits - 1,
+                EditOperation::Insertion,
+                search,
+            );
+        }
+
+        if indels_allowed && !(canonical && previous == EditOperation::Insertion) {
+            for &symbol in search.symbols {
+                let next = Self::extend_cursor_front_for_benchmark(index, cursor, symbol);
+                search.num_explored_intervals += 1;
+
+                Self::locate_with_edits(
+                    index,
+                    &next,
+                    remaining_query,
+                    max_edits - 1,
+                    EditOperation::Deletion,
+                    search,
+                );
+            }
+        }
+    }
+
+    // For every end position, the longest match ending there is computed by backward extension. It is a super-maximal
+    // exact match, if the longest match ending one position further right starts further right. Only the seeds are
+    // counted, because repetitive seeds can have a huge number of occurrences. A bidirectional index could avoid
+    // starting the backward extension from scratch for every end position.
+    fn count_smems<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+        min_seed_length: usize,
+        counts: &mut SearchCounts,
+    ) {
+        // start of the longest match ending one position further right
+        let mut previous_start = None;
+        let mut num_seeds = 0;
+
+        for end in (1..=query.len()).rev() {
+            let mut cursor = Self::cursor_for_benchmark(index);
+            let mut start = end;
+
+            while start > 0 {
+                let next =
+                    Self::extend_cursor_front_for_benchmark(index, &cursor, query[start - 1]);
+                counts.num_explored_intervals += 1;
+
+                if Self::cursor_count_for_benchmark(index, &next) == 0 {
+                    break;
+                }
+
+                cursor = next;
+                start -= 1;
+            }
+
+            let seed_length = end - start;
+            if previous_start.is_none_or(|previous_start| start < previous_start)
+                && seed_length > 0
+                && seed_length >= min_seed_length
+            {
+                num_seeds += 1;
+                counts.seeds.total_seed_length += seed_length;
+                counts.num_forward_hits += Self::cursor_count_for_benchmark(index, &cursor);
+            }
+
+            // all matches ending further left are contained in this one
+            if start == 0 {
+                break;
+            }
+
+            previous_start = Some(start);
+        }
+
+        counts.seeds.num_seeds += num_seeds;
+        if num_seeds == 0 {
+            counts.seeds.num_queries_without_seeds += 1;
+        }
+    }
+
+    // The queries are sorted by their reversed sequence, which is the order of a trie over the reversed queries.
+    // Then consecutive queries share the intervals of their common suffix, which are kept on a stack.
+    // The sorting is part of the measured time.
+    fn trie_count_many<'a>(index: &Self::IndexRef<'a>, queries: &Queries) -> SearchCounts {
+        let mut sorted_queries: Vec<_> = queries.iter().collect();
+        sorted_queries.sort_unstable_by(|a, b| a.iter().rev().cmp(b.iter().rev()));
+
+        let mut counts = SearchCounts::default();
+        // the cursor at position i has the suffix of length i of the previous query searched
+        let mut cursors = vec![Self::cursor_for_benchmark(index)];
+        let mut previous_query: &[u8] = &[];
+
+        for query in sorted_queries {
+            let shared_suffix_length = query
+                .iter()
+                .rev()
+                .zip(previous_query.iter().rev())
+                .take_while(|(a, b)| a == b)
+                .count();
+            cursors.truncate(shared_suffix_length + 1);
+            counts.num_reused_intervals += cursors.len() - 1;
+
+            for &symbol in query[..query.len() + 1 - cursors.len()].iter().rev() {
+                let cursor = cursors.last().unwrap();
+
+                // all extensions of a suffix without occurrences have no occurrences either
+                if Self::cursor_count_for_benchmark(index, cursor) == 0 {
+                    break;
+                }
+
+                let next = Self::extend_cursor_front_for_benchmark(index, cursor, symbol);
+                counts.num_explored_intervals += 1;
+                cursors.push(next);
+            }
+
+            if cursors.len() == query.len() + 1 {
+                counts.num_forward_hits +=
+                    Self::cursor_count_for_benchmark(index, cursors.last().unwrap());
+            }
+
+            previous_query = query;
+        }
+
+        counts
+    }
+
+    // the reverse complements are computed as part of the search, like a read mapper would do
+    fn search_many_strands<'a>(
+        index: &Self::IndexRef<'a>,
+        queries: &Queries,
+        reverse_queries: &mut Queries,
+        config: &Config,
+    ) -> SearchCounts {
+        let mut counts = Self::search_many(index, queries, config);
+
+        if config.both_strands {
+            queries.reverse_complement_into(reverse_queries);
+            let reverse_counts = Self::search_many(index, reverse_queries, config);
+
+            counts.num_reverse_hits = reverse_counts.num_forward_hits;
+            counts.num_explored_intervals += reverse_counts.num_explored_intervals;
+            counts.num_reused_intervals += reverse_counts.num_reused_intervals;
+            counts.seeds += reverse_counts.seeds;
+            counts.longest_suffix += reverse_counts.longest_suffix;
+        }
+
+        counts
+    }
+
+    fn time_search<'a>(
+        index: &Self::IndexRef<'a>,
+        queries: &Queries,
+        config: &Config,
+    ) -> SearchMetrics {
+        let mut counts = SearchCounts::default();
+        let mut running_times_secs = Vec::new();
+        let mut reverse_queries = Queries::new();
+
+        for _ in 0..config.repeat_search {
+            let start = std::time::Instant::now();
+            counts = Self::search_many_strands(index, queries, &mut reverse_queries, config);
+            running_times_secs.push(start.elapsed().as_secs_f64());
+        }
+
+        let mut search_metrics =
+            summarize_search(&running_times_secs, counts, queries.len(), config);
+
+        if config.search_mode == SearchMode::Locate {
+            search_metrics.locate_phases = Some(Self::time_locate_phases(
+                index,
+                queries,
+                search_metrics.min_time_secs,
+                config,
+            ));
+        }
+
+        search_metrics
+    }
+
+    // With a cursor, the backward search of all queries and the position resolution via the sampled suffix array
+    // are timed separately. Otherwise, the time of the position resolution is derived as locate minus count.
+    fn time_locate_phases<'a>(
+        index: &Self::IndexRef<'a>,
+        queries: &Queries,
+        locate_time_secs: f64,
+        config: &Config,
+    ) -> LocatePhaseStats {
+        let mut reverse_queries = Queries::new();
+        if config.both_strands {
+            queries.reverse_complement_into(&mut reverse_queries);
+        }
+        let searched_queries = || queries.iter().chain(reverse_queries.iter());
+
+        let mut backward_search_times_secs = Vec::new();
+        let mut position_resolution_times_secs = Vec::new();
+
+        for _ in 0..config.repeat_search {
+            if Self::CAPABILITIES.cursor {
+                // allocated outside of the measured region
+                let mut cursors = Vec::with_capacity(queries.len() + reverse_queries.len());
+
+                let start = std::time::Instant::now();
+                cursors.extend(
+                    searched_queries().map(|query| Self::search_cursor_for_benchmark(index, query)),
+                );
+                backward_search_times_secs.push(start.elapsed().as_secs_f64());
+
+                let start = std::time::Instant::now();
+                std::hint::black_box(
+                    cursors
+                        .iter()
+                        .map(|cursor| Self::cursor_count_via_locate_for_benchmark(index, cursor))
+                        .sum::<usize>(),
+                );
+                position_resolution_times_secs.push(start.elapsed().as_secs_f64());
+            } else {
+                let start = std::time::Instant::now();
+                std::hint::black_box(
+                    Self::count_many_for_benchmark(index, queries)
+                        + Self::count_many_for_benchmark(index, &reverse_queries),
+                );
+                backward_search_times_secs.push(start.elapsed().as_secs_f64());
+            }
+        }
+
+        let min_secs = |times_secs: &[f64]| {
+            times_secs
+                .iter()
+                .copied()
+                .min_by(|a, b| a.total_cmp(b))
+                .unwrap_or(0.0)
+        };
+
+        let backward_search_time_secs = min_secs(&backward_search_times_secs);
+        let derived = !Self::CAPABILITIES.cursor;
+        let position_resolution_time_secs = if derived {
+            (locate_time_secs - backward_search_time_secs).max(0.0)
+        } else {
+            min_secs(&position_resolution_times_secs)
+        };
 
         info!(
-            "Search queries time: {min_time_secs:.2} (min), {avg_time_secs:.2} (avg) seconds, total number of hits: {total_num_hits}"
+            "Locate phases: backward search {backward_search_time_secs:.3} seconds, position resolution {position_resolution_time_secs:.3} seconds{}",
+            if derived {
+                " (derived as locate minus count)"
+            } else {
+                ""
+            }
         );
 
-        SearchMetrics {
-            min_time_secs,
-            avg_time_secs,
+        LocatePhaseStats {
+            backward_search_time_secs,
+            position_resolution_time_secs,
+            derived,
+        }
+    }
+
+    // the measured time includes reading the queries from disk
+    fn time_streaming_search<'a>(
+        index: &Self::IndexRef<'a>,
+        chunk_size: usize,
+        config: &Config,
+    ) -> Result<SearchMetrics, BenchmarkError> {
+        let mut counts = SearchCounts::default();
+        let mut running_times_secs = Vec::new();
+        let mut searching_times_secs = Vec::new();
+        let mut degenerate_symbol_stats = None;
+        let mut num_queries = 0;
+
+        for _ in 0..config.repeat_search {
+            let start = std::time::Instant::now();
+            let mut searching_time = std::time::Duration::ZERO;
+
+            let mut query_stream = QueryStream::open(config, chunk_size)?;
+            let mut chunk = Queries::new();
+            let mut reverse_chunk = Queries::new();
+            counts = SearchCounts::default();
+            num_queries = 0;
+
+            while query_stream.next_chunk(&mut chunk)? {
+                let search_start = std::time::Instant::now();
+                counts += Self::search_many_strands(index, &chunk, &mut reverse_chunk, config);
+                searching_time += search_start.elapsed();
+                num_queries += chunk.len();
+            }
+
+            running_times_secs.push(start.elapsed().as_secs_f64());
+            searching_times_secs.push(searching_time.as_secs_f64());
+            degenerate_symbol_stats = Some(query_stream.degenerate_symbol_stats());
         }
+
+        info!(
+            "Streamed queries in chunks of {chunk_size}, time spent in the search: {:.2} (min) seconds",
+            searching_times_secs
+                .iter()
+                .min_by(|a, b| a.total_cmp(b))
+                .unwrap()
+        );
+
+        let mut search_metrics = summarize_search(&running_times_secs, counts, num_queries, config);
+        search_metrics.degenerate_queries = degenerate_symbol_stats;
+
+        Ok(search_metrics)
     }
 
-    fn run_io_benchmark(self, config: &Config) -> Option<FileIoMetrics> {
+    fn run_io_benchmark(self, config: &Config) -> Result<Option<FileIoMetrics>, BenchmarkError> {
         let index_filepath = config.index_filepath();
 
-        if !std::fs::exists(&index_filepath).unwrap() || config.force_write_and_load {
+        if !index_filepath.exists() || config.force_write_and_load {
             let start = std::time::Instant::now();
-            self.write_to_file_for_benchmark(&index_filepath);
-            let write_secs = start.elapsed().as_millis() as f64 / 1_000.0;
+            self.write_to_file_for_benchmark(&index_filepath)?;
+            let write_secs = start.elapsed().as_secs_f64();
             info!("Write to disk time: {write_secs:.2} seconds");
 
             let start = std::time::Instant::now();
-            let index = Self::load_from_file_for_benchmark(&index_filepath);
-            let index_stub = Self::as_stub_for_benchmark(&index);
-            let read_secs = start.elapsed().as_millis() as f64 / 1_000.0;
+            let index = Self::load_from_file_for_benchmark(&index_filepath)?;
+            let index_stub = Self::as_stub_for_benchmark(&index)?;
+            let read_secs = start.elapsed().as_secs_f64();
 
             info!(
                 "Load from disk time: {read_secs:.2} seconds (dummy: {})",
                 Self::count_via_locate_for_benchmark(&index_stub, b"ACGT")
             );
 
-            Some(FileIoMetrics {
+            Ok(Some(FileIoMetrics {
                 read_secs,
                 write_secs,
-            })
+            }))
         } else {
-            None
+            Ok(None)
         }
     }
 
-    fn run_benchmark(config: &Config) -> BenchmarkResult {
+    fn run_benchmark(config: &Config) -> Result<BenchmarkResult, BenchmarkError> {
+        check_alphabet(config, &Self::CAPABILITIES)?;
+
+        if config.soft_mask && (config.alphabet() != Alphabet::Dna || !Self::CAPABILITIES.locate) {
+            return Err(BenchmarkError::Unsupported(format!(
+                "the soft mask requires DNA texts and a library that supports locate, which {} doesn't",
+                config.library
+            )));
+        }
+
+        // the mask is computed from the original records, dropping or splitting texts changes the text ids and offsets
+        if config.soft_mask
+            && !matches!(
+                config.texts_degenerate_policy(),
+                DegeneratePolicy::Replace(_)
+            )
+        {
+            return Err(BenchmarkError::Unsupported(String::from(
+                "the soft mask requires that the degenerate symbols of the texts are replaced",
+            )));
+        }
+
+        if config.soft_mask && config.stream_queries.is_some() {
+            return Err(BenchmarkError::Unsupported(String::from(
+                "the soft mask requires all queries in memory, so they can't be streamed",
+            )));
+        }
+
+        if config.query_order != QueryOrder::File && config.stream_queries.is_some() {
+            return Err(BenchmarkError::Unsupported(String::from(
+                "the query order can only be changed with all queries in memory, so they can't be streamed",
+            )));
+        }
+
+        if config.hit_stats && config.stream_queries.is_some() {
+            return Err(BenchmarkError::Unsupported(String::from(
+                "the hit statistics require all queries in memory, so they can't be streamed",
+            )));
+        }
+
+        if config.both_strands && config.alphabet() != Alphabet::Dna {
+            return Err(BenchmarkError::Unsupported(String::from(
+                "only DNA queries have a reverse complement",
+            )));
+        }
+
+        if config.search_mode.uses_cursor() && !Self::CAPABILITIES.cursor {
+            return Err(BenchmarkError::Unsupported(format!(
+                "the {} search mode requires a backward search cursor, which {} doesn't provide",
+                config.search_mode, config.library
+            )));
+        }
+
+        if config.stream_queries == Some(0) {
+            return Err(BenchmarkError::Unsupported(String::from(
+                "the queries can't be streamed in chunks of size 0",
+            )));
+        }
+
+        if config.lookup_table_depth() != config.depth_of_lookup_table {
+            info!(
+                "The lookup table depth is reduced from {} to {} for the {} alphabet",
+                config.depth_of_lookup_table,
+                config.lookup_table_depth(),
+                config.alphabet()
+            );
+        }
+
         let mut result = BenchmarkResult::new_empty(config.clone());
 
-        let (index, construction_metrics) = Self::construct_or_load_for_benchmark(config);
+        let (index, construction_metrics) = Self::construct_or_load_for_benchmark(config)?;
 
         if construction_metrics.was_constructed {
             result.construction_peak_memory_usage_mb =
@@ -162,142 +977,386 @@ pub trait BenchmarkFmIndex: Sized {
         }
 
         result.only_index_in_memory_size_mb = Some(construction_metrics.curr_memory_usage_mb);
+        result.texts_size_mb = construction_metrics.texts_size_mb;
+        result.texts_degenerate_symbols = construction_metrics.texts_degenerate_symbols;
 
-        if config.search_mode == SearchMode::Locate && !Self::supports_locate_for_benchmark() {
+        if config.search_mode.locates() && !Self::CAPABILITIES.locate {
             info!("Currently, {} does not support locate.", config.library);
         } else {
-            let search_metrics = index.run_search_benchmark(config);
-
-            result
-                .search_metrics
-                .insert(config.search_config().to_string(), search_metrics);
+            for config in config.configs_for_query_lengths() {
+                if let Some(length_of_queries) = config.length_of_queries
+                    && !config.query_length_sweep.is_empty()
+                {
+                    info!("Query length: {length_of_queries}");
+                }
+
+                for (search_config, mut search_metrics) in index.run_search_benchmark(&config)? {
+                    if !config.query_length_sweep.is_empty() {
+                        search_metrics.swept_search_config = Some(config.search_config());
+                    }
+                    result.search_metrics.insert(search_config, search_metrics);
+                }
+            }
         }
 
-        if Self::supports_file_io_for_benchmark(config) {
-            let file_io_metrics = index.run_io_benchmark(config);
+        if Self::CAPABILITIES.runs_file_io_for(config) {
+            let file_io_metrics = index.run_io_benchmark(config)?;
 
             result.read_from_file_time_secs = file_io_metrics.map(|m| m.read_secs);
             result.write_to_file_time_secs = file_io_metrics.map(|m| m.write_secs);
         }
 
-        result
+        Ok(result)
     }
 }
 
-#[derive(Clone, Copy)]
-pub struct ConstructionMetrics {
-    elapsed_time_secs: f64,
-    peak_memory_usage_mb: f64,
-    curr_memory_usage_mb: f64,
-    was_constructed: bool,
+// Chooses the index type depending on the size of the input texts. The types are for texts whose positions
+// fit into an i32, fit into a u32 and need 64 bits, respectively.
+pub fn run_benchmark_for_text_size<Small, Medium, Large>(
+    config: &Config,
+) -> Result<BenchmarkResult, BenchmarkError>
+where
+    Small: BenchmarkFmIndex,
+    Medium: BenchmarkFmIndex,
+    Large: BenchmarkFmIndex,
+{
+    match config.input_texts {
+        InputTexts::Chromosome | InputTexts::I32 => Small::run_benchmark(config),
+        InputTexts::Hg38 => Medium::run_benchmark(config),
+        InputTexts::DoubleHg38 => Large::run_benchmark(config),
+        // the size of these inputs varies a lot, the file size is an upper bound for the text length,
+        // unless the file is compressed
+        InputTexts::Proteome
+        | InputTexts::English
+        | InputTexts::Sources
+        | InputTexts::Xml
+        | InputTexts::Logs => {
+            let path = config.input_texts.get_filepath();
+            let file_size = std::fs::metadata(&path)
+                .map_err(|e| BenchmarkError::input(&path, e))?
+                .len();
+
+            if Compression::of_file(&path)? != Compression::None {
+                Large::run_benchmark(config)
+            } else if file_size < i32::MAX as u64 {
+                Small::run_benchmark(config)
+            } else if file_size < u32::MAX as u64 {
+                Medium::run_benchmark(config)
+            } else {
+                Large::run_benchmark(config)
+            }
+        }
+    }
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Copy)]
-pub struct SearchMetrics {
-    min_time_secs: f64,
-    avg_time_secs: f64,
-}
+fn summarize_search(
+    running_times_secs: &[f64],
+    counts: SearchCounts,
+    num_queries: usize,
+    config: &Config,
+) -> SearchMetrics {
+    let total_num_hits = counts.num_forward_hits + counts.num_reverse_hits;
 
-#[derive(Clone, Copy)]
-pub struct FileIoMetrics {
-    read_secs: f64,
-    write_secs: f64,
-}
+    let &min_time_secs = running_times_secs
+        .iter()
+        .min_by(|a, b| a.total_cmp(b))
+        .unwrap();
+    let avg_time_secs = running_times_secs.iter().sum::<f64>() / running_times_secs.len() as f64;
+
+    info!(
+        "Search queries time: {min_time_secs:.2} (min), {avg_time_secs:.2} (avg) seconds, total number of hits: {total_num_hits}"
+    );
+
+    if config.both_strands {
+        info!(
+            "Hits on the forward strand: {}, on the reverse strand: {}",
+            counts.num_forward_hits, counts.num_reverse_hits
+        );
+    }
+
+    let num_explored_intervals = config
+        .search_mode
+        .uses_cursor()
+        .then_some(counts.num_explored_intervals);
 
-fn read_texts(config: &Config) -> Vec<Vec<u8>> {
-    let start = std::time::Instant::now();
+    if let Some(num_explored_intervals) = num_explored_intervals {
+        info!("Explored intervals of the backward search cursor: {num_explored_intervals}");
+    }
+
+    let num_reused_intervals =
+        (config.search_mode == SearchMode::TrieCount).then_some(counts.num_reused_intervals);
 
-    let reader = bio::io::fasta::Reader::from_file(config.input_texts.get_filepath()).unwrap();
-    let mut seqs: Vec<_> = reader
-        .records()
-        .map(|r| r.unwrap().seq().to_vec())
-        .collect();
+    if let Some(num_reused_intervals) = num_reused_intervals {
+        info!("Reused intervals of shared query suffixes: {num_reused_intervals}");
+    }
 
-    transfrom_seqs(&mut seqs, "texts", b'N', config.verbose);
+    let seeds = (config.search_mode == SearchMode::Smems).then_some(counts.seeds);
+    let longest_suffix =
+        (config.search_mode == SearchMode::LongestSuffix).then_some(counts.longest_suffix);
 
-    if config.verbose {
+    if let Some(seeds) = seeds {
         info!(
-            "Texts reading time: {:.2} seconds",
-            start.elapsed().as_millis() as f64 / 1_000.0
+            "Seeds: {}, average seed length: {:.1}, queries without seeds: {}",
+            seeds.num_seeds,
+            seeds.total_seed_length as f64 / seeds.num_seeds.max(1) as f64,
+            seeds.num_queries_without_seeds
         );
     }
 
-    seqs
-}
+    if let Some(longest_suffix) = longest_suffix {
+        // the reverse complements are searched as separate queries
+        let num_searched_queries = num_queries * if config.both_strands { 2 } else { 1 };
+
+        info!(
+            "Average length of the longest matching suffix: {:.1}, complete matches: {}",
+            longest_suffix.total_suffix_length as f64 / num_searched_queries.max(1) as f64,
+            longest_suffix.num_complete_matches
+        );
+    }
 
-fn read_queries(config: &Config) -> Vec<Vec<u8>> {
-    let start = std::time::Instant::now();
+    SearchMetrics {
+        min_time_secs,
+        avg_time_secs,
+        num_queries: Some(num_queries),
+        degenerate_queries: None,
+        soft_mask: None,
+        strands: config.both_strands.then_some(StrandStats {
+            num_forward_hits: counts.num_forward_hits,
+            num_reverse_hits: counts.num_reverse_hits,
+        }),
+        num_explored_intervals,
+        num_reused_intervals,
+        seeds,
+        longest_suffix,
+        hit_stats: None,
+        locate_phases: None,
+        swept_search_config: None,
+    }
+}
 
-    let reader = bio::io::fastq::Reader::from_file(&config.queries_path).unwrap();
-    let mut seqs = Vec::new();
+fn check_alphabet(config: &Config, capabilities: &Capabilities) -> Result<(), BenchmarkError> {
+    let alphabet = config.alphabet();
 
-    for (i, record) in reader.records().enumerate() {
-        if let Some(n) = config.num_queries_records
-            && i == n
+    // the replacement symbols end up in the texts and queries, which the libraries only support for their alphabet
+    for policy in [
+        config.texts_degenerate_policy(),
+        config.queries_degenerate_policy(),
+    ] {
+        if let DegeneratePolicy::Replace(symbol) = policy
+            && !alphabet.symbols().contains(&symbol)
         {
-            break;
+            return Err(BenchmarkError::Unsupported(format!(
+                "the replacement symbol {} is not part of the {alphabet} alphabet",
+                symbol.escape_ascii()
+            )));
         }
+    }
 
-        let record = record.unwrap();
-        let mut slice = record.seq();
-        if let Some(l) = config.length_of_queries {
-            slice = &slice[..std::cmp::min(l, slice.len())];
-        }
-        seqs.push(slice.to_vec());
+    if !capabilities.alphabets.contains(&alphabet) {
+        return Err(BenchmarkError::Unsupported(format!(
+            "{} doesn't support the {alphabet} alphabet",
+            config.library
+        )));
     }
 
-    transfrom_seqs(&mut seqs, "queries", b'A', config.verbose);
+    Ok(())
+}
 
-    if config.verbose {
-        info!(
-            "Queries reading time: {:.2} seconds",
-            start.elapsed().as_millis() as f64 / 1_000.0
-        );
+#[derive(Clone, Copy)]
+pub struct Capabilities {
+    pub multiple_texts: bool,
+    pub file_io: FileIoSupport,
+    pub file_io_mmap: bool,
+    pub multithreaded_construction: bool,
+    // only temporary, until awry's issue is fixed
+    pub locate: bool,
+    // the library exposes the single steps of the backward search
+    pub cursor: bool,
+    // the library reads the input file by itself, so the harness doesn't need to provide the texts
+    pub reads_texts_from_file: bool,
+    pub alphabets: &'static [Alphabet],
+}
+
+impl Capabilities {
+    fn runs_file_io_for(&self, config: &Config) -> bool {
+        match self.file_io {
+            FileIoSupport::Unsupported => false,
+            // very slow IO would dominate the running time of the whole benchmark for large inputs
+            FileIoSupport::VerySlow => config.input_texts.is_small(),
+            FileIoSupport::Slow | FileIoSupport::Fast => true,
+        }
     }
+}
 
-    seqs
+// ordered from worst to best support
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FileIoSupport {
+    Unsupported,
+    // usually due to the usage of slow serializers, only benchmarked for small inputs
+    VerySlow,
+    Slow,
+    Fast,
 }
 
-fn transfrom_seqs(seqs: &mut [Vec<u8>], name: &str, replacement_symbol: u8, verbose: bool) {
-    let mut translation_table: Vec<_> = (0u8..=255).collect();
-    for degenerate_symbol in b"rRyYkKMmSsWwBbDdHhVvNn".iter().copied() {
-        translation_table[degenerate_symbol as usize] = replacement_symbol;
+#[derive(Clone, Copy)]
+pub struct ConstructionMetrics {
+    elapsed_time_secs: f64,
+    peak_memory_usage_mb: f64,
+    curr_memory_usage_mb: f64,
+    texts_size_mb: Option<f64>,
+    texts_degenerate_symbols: Option<DegenerateSymbolStats>,
+    was_constructed: bool,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct SearchMetrics {
+    pub min_time_secs: f64,
+    pub avg_time_secs: f64,
+    #[serde(default)]
+    pub num_queries: Option<usize>,
+    #[serde(default)]
+    pub degenerate_queries: Option<DegenerateSymbolStats>,
+    #[serde(default)]
+    pub soft_mask: Option<SoftMaskStats>,
+    #[serde(default)]
+    pub strands: Option<StrandStats>,
+    // the number of extensions of the backward search cursor by the search modes that use it
+    #[serde(default)]
+    pub num_explored_intervals: Option<usize>,
+    // the number of extensions saved by the trie count, because the query suffix was shared with the previous query
+    #[serde(default)]
+    pub num_reused_intervals: Option<usize>,
+    #[serde(default)]
+    pub seeds: Option<SeedStats>,
+    #[serde(default)]
+    pub longest_suffix: Option<LongestSuffixStats>,
+    #[serde(default)]
+    pub hit_stats: Option<HitStats>,
+    #[serde(default)]
+    pub locate_phases: Option<LocatePhaseStats>,
+    // only set for the searches of a query length sweep, the results of a sweep can be updated by later runs
+    #[serde(default)]
+    pub swept_search_config: Option<SearchConfig>,
+}
+
+// the minimum times of the two phases of locate over the repeated searches
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy)]
+pub struct LocatePhaseStats {
+    pub backward_search_time_secs: f64,
+    pub position_resolution_time_secs: f64,
+    // the library has no cursor, so the position resolution time is the locate time minus the count time
+    pub derived: bool,
+}
+
+// the hits of the seeds are stored as the hits of the search
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Default)]
+pub struct SeedStats {
+    pub num_seeds: usize,
+    pub total_seed_length: usize,
+    pub num_queries_without_seeds: usize,
+}
+
+impl std::ops::AddAssign for SeedStats {
+    fn add_assign(&mut self, other: Self) {
+        self.num_seeds += other.num_seeds;
+        self.total_seed_length += other.total_seed_length;
+        self.num_queries_without_seeds += other.num_queries_without_seeds;
     }
-    translation_table[b'a' as usize] = b'A';
-    translation_table[b'c' as usize] = b'C';
-    translation_table[b'g' as usize] = b'G';
-    translation_table[b't' as usize] = b'T';
+}
 
-    for seq in seqs.iter_mut() {
-        for symbol in seq.iter_mut() {
-            *symbol = translation_table[*symbol as usize];
-        }
+// the reverse strand hits are the hits of the reverse complements of the queries
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy)]
+pub struct StrandStats {
+    pub num_forward_hits: usize,
+    pub num_reverse_hits: usize,
+}
+
+// the number of occurrences of the longest suffixes is stored as the hits of the search
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Default)]
+pub struct LongestSuffixStats {
+    pub total_suffix_length: usize,
+    // the whole query occurs in the texts
+    pub num_complete_matches: usize,
+}
+
+impl std::ops::AddAssign for LongestSuffixStats {
+    fn add_assign(&mut self, other: Self) {
+        self.total_suffix_length += other.total_suffix_length;
+        self.num_complete_matches += other.num_complete_matches;
     }
+}
 
-    let texts_len: usize = seqs.iter().map(|t| t.len()).sum();
+pub struct SoftMaskClassifiedQueries {
+    repeats: Queries,
+    unique: Queries,
+    no_hits: Queries,
+}
 
-    let average_record_length =
-        seqs.iter().map(|s| s.len()).sum::<usize>() as f64 / seqs.len() as f64;
+// accumulated over all queries of a search run
+#[derive(Clone, Copy, Default)]
+pub struct SearchCounts {
+    num_forward_hits: usize,
+    num_reverse_hits: usize,
+    num_explored_intervals: usize,
+    num_reused_intervals: usize,
+    seeds: SeedStats,
+    longest_suffix: LongestSuffixStats,
+}
 
-    if verbose {
-        info!(
-            "Total length of {name}: {} MB, average record length: {:.1}",
-            texts_len / 1_000_000,
-            average_record_length
-        );
+impl std::ops::AddAssign for SearchCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.num_forward_hits += other.num_forward_hits;
+        self.num_reverse_hits += other.num_reverse_hits;
+        self.num_explored_intervals += other.num_explored_intervals;
+        self.num_reused_intervals += other.num_reused_intervals;
+        self.seeds += other.seeds;
+        self.longest_suffix += other.longest_suffix;
     }
+}
 
-    info!(
-        "Current memory usage after reading {name}: {:.1} MB",
-        process_current_memory_usage_mb()
-    );
+// shared by all branches of the approximate search
+pub struct ApproximateSearch {
+    symbols: &'static [u8],
+    edit_pruning: EditPruning,
+    hits: Vec<HitPosition>,
+    num_explored_intervals: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum EditOperation {
+    Start,
+    Match,
+    Substitution,
+    Insertion,
+    Deletion,
+}
+
+#[derive(Clone, Copy)]
+pub enum HitPosition {
+    InText {
+        text_id: usize,
+        position: usize,
+    },
+    // in the concatenation of all texts, with separator_len symbols between the texts
+    Concatenated {
+        position: usize,
+        separator_len: usize,
+    },
+}
+
+#[derive(Clone, Copy)]
+pub struct FileIoMetrics {
+    read_secs: f64,
+    write_secs: f64,
 }
 
 fn collect_and_log_after_build_metrics(
     start: std::time::Instant,
     was_constructed: bool,
+    texts_size_mb: Option<f64>,
+    texts_degenerate_symbols: Option<DegenerateSymbolStats>,
 ) -> ConstructionMetrics {
-    let elapsed_time_secs = start.elapsed().as_millis() as f64 / 1_000.0;
+    let elapsed_time_secs = start.elapsed().as_secs_f64();
     let peak_memory_usage_mb = process_peak_memory_usage_mb();
     let curr_memory_usage_mb = process_current_memory_usage_mb();
 
@@ -310,6 +1369,8 @@ fn collect_and_log_after_build_metrics(
         elapsed_time_secs,
         peak_memory_usage_mb,
         curr_memory_usage_mb,
+        texts_size_mb,
+        texts_degenerate_symbols,
         was_constructed,
     }
 }
@@ -336,7 +1397,7 @@ fn process_peak_memory_usage_mb() -> f64 {
 }
 
 #[cfg(windows)]
-fn process_current_memory_usage_mb() -> f64 {
+pub fn process_current_memory_usage_mb() -> f64 {
     get_memory_info().WorkingSetSize as f64 / 1_000_000.0
 }
 
@@ -351,7 +1412,7 @@ fn process_peak_memory_usage_mb() -> f64 {
 }
 
 #[cfg(unix)]
-fn process_current_memory_usage_mb() -> f64 {
+pub fn process_current_memory_usage_mb() -> f64 {
     let statm = std::fs::read_to_string("/proc/self/statm").unwrap();
     let fields: Vec<&str> = statm.split_whitespace().collect();
 
@@ -359,3 +1420,148 @@ fn process_current_memory_usage_mb() -> f64 {
     let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as u64;
     (num_pages * page_size) as f64 / 1_000_000.0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Counts the occurrences by scanning the text. The cursor is the currently searched query, such that the search
+    // modes that are built on the cursor can be checked without a library.
+    struct NaiveIndex {
+        text: Vec<u8>,
+    }
+
+    impl BenchmarkFmIndex for NaiveIndex {
+        type IndexRef<'a> = &'a Self;
+        type Cursor<'a> = Vec<u8>;
+
+        const CAPABILITIES: Capabilities = Capabilities {
+            multiple_texts: false,
+            file_io: FileIoSupport::Unsupported,
+            file_io_mmap: false,
+            multithreaded_construction: false,
+            locate: false,
+            cursor: true,
+            reads_texts_from_file: false,
+            alphabets: &[Alphabet::Dna],
+        };
+
+        fn as_stub_for_benchmark<'a>(&'a self) -> Result<Self::IndexRef<'a>, BenchmarkError> {
+            Ok(self)
+        }
+
+        fn construct_for_benchmark(
+            _config: &Config,
+            texts: Option<Texts>,
+        ) -> Result<Self, BenchmarkError> {
+            Ok(Self {
+                text: texts.unwrap().into_symbols(),
+            })
+        }
+
+        // like for an FM-index, the empty query occurs at every position
+        fn count_for_benchmark<'a>(index: &Self::IndexRef<'a>, query: &[u8]) -> usize {
+            if query.is_empty() {
+                return index.text.len();
+            }
+
+            index
+                .text
+                .windows(query.len())
+                .filter(|window| *window == query)
+                .count()
+        }
+
+        fn count_via_locate_for_benchmark<'a>(index: &Self::IndexRef<'a>, query: &[u8]) -> usize {
+            Self::count_for_benchmark(index, query)
+        }
+
+        fn cursor_for_benchmark<'a>(_index: &Self::IndexRef<'a>) -> Self::Cursor<'a> {
+            Vec::new()
+        }
+
+        fn extend_cursor_front_for_benchmark<'a>(
+            _index: &Self::IndexRef<'a>,
+            cursor: &Self::Cursor<'a>,
+            symbol: u8,
+        ) -> Self::Cursor<'a> {
+            [&[symbol][..], cursor].concat()
+        }
+
+        fn cursor_count_for_benchmark<'a>(
+            index: &Self::IndexRef<'a>,
+            cursor: &Self::Cursor<'a>,
+        ) -> usize {
+            Self::count_for_benchmark(index, cursor)
+        }
+    }
+
+    fn naive_index(text: &[u8]) -> NaiveIndex {
+        NaiveIndex {
+            text: text.to_vec(),
+        }
+    }
+
+    fn smem_counts(text: &[u8], queries: &[&[u8]], min_seed_length: usize) -> SearchCounts {
+        let index = naive_index(text);
+        let mut counts = SearchCounts::default();
+        for query in queries {
+            NaiveIndex::count_smems(&&index, query, min_seed_length, &mut counts);
+        }
+        counts
+    }
+
+    #[test]
+    fn count_smems() {
+        // the SMEMs of the query are ACGT (once in the text), A (twice) and GCA (once)
+        let counts = smem_counts(b"ACGTTGCA", &[b"ACGTAGCA"], 1);
+        assert_eq!(counts.seeds.num_seeds, 3);
+        assert_eq!(counts.seeds.total_seed_length, 8);
+        assert_eq!(counts.seeds.num_queries_without_seeds, 0);
+        assert_eq!(counts.num_forward_hits, 4);
+
+        // the seed A is too short
+        let counts = smem_counts(b"ACGTTGCA", &[b"ACGTAGCA"], 2);
+        assert_eq!(counts.seeds.num_seeds, 2);
+        assert_eq!(counts.seeds.total_seed_length, 7);
+        assert_eq!(counts.num_forward_hits, 2);
+    }
+
+    #[test]
+    fn count_smems_of_whole_and_unmatched_queries() {
+        // a query that occurs as a whole is a single seed
+        let counts = smem_counts(b"ACGTTGCA", &[b"GTTG", b"TTTT", b"CCC"], 3);
+        assert_eq!(counts.seeds.num_seeds, 1);
+        assert_eq!(counts.seeds.total_seed_length, 4);
+        assert_eq!(counts.seeds.num_queries_without_seeds, 2);
+        assert_eq!(counts.num_forward_hits, 1);
+    }
+
+    #[test]
+    fn trie_count_many_matches_count() {
+        let index = naive_index(b"ACGTACGTTACGA");
+        let mut queries = Queries::new();
+        // shared suffixes, a duplicate, an absent query and a query that is longer than the text
+        for query in [
+            &b"ACG"[..],
+            b"TACG",
+            b"CG",
+            b"ACG",
+            b"GGG",
+            b"ACGTACGTTACGAAC",
+        ] {
+            queries.push(query);
+        }
+
+        let counts = NaiveIndex::trie_count_many(&&index, &queries);
+
+        let expected_hits: usize = queries
+            .iter()
+            .map(|query| NaiveIndex::count_for_benchmark(&&index, query))
+            .sum();
+        assert_eq!(expected_hits, 3 + 2 + 3 + 3);
+        assert_eq!(counts.num_forward_hits, expected_hits);
+        // in trie order, CG is followed by ACG, ACG, TACG and GGG, which reuse 2, 3, 3 and 1 intervals
+        assert_eq!(counts.num_reused_intervals, 9);
+    }
+}
@@ -0,0 +1,143 @@
+use std::cell::Cell;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::error::BenchmarkError;
+
+// checked in this order, if the uncompressed input file doesn't exist
+const COMPRESSED_FILE_EXTENSIONS: [&str; 3] = ["gz", "bgz", "zst"];
+
+const GZIP_MAGIC_BYTES: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC_BYTES: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    // bgzip files are valid multi-member gzip files
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    // detected by the magic bytes, not the file extension
+    pub fn of_file(path: &Path) -> Result<Self, BenchmarkError> {
+        let mut magic_bytes = Vec::with_capacity(ZSTD_MAGIC_BYTES.len());
+        File::open(path)
+            .and_then(|file| {
+                file.take(ZSTD_MAGIC_BYTES.len() as u64)
+                    .read_to_end(&mut magic_bytes)
+            })
+            .map_err(|e| BenchmarkError::input(path, e))?;
+
+        let compression = if magic_bytes.starts_with(&GZIP_MAGIC_BYTES) {
+            Compression::Gzip
+        } else if magic_bytes.starts_with(&ZSTD_MAGIC_BYTES) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        };
+
+        Ok(compression)
+    }
+}
+
+impl Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Compression::None => write!(f, "uncompressed"),
+            Compression::Gzip => write!(f, "gzip"),
+            Compression::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+// Returns the first existing one of the path itself and the path with a compressed file extension appended.
+// If none exists, the path itself is returned, such that errors mention the expected file.
+pub fn find_input_file(path: PathBuf) -> PathBuf {
+    if path.exists() {
+        return path;
+    }
+
+    COMPRESSED_FILE_EXTENSIONS
+        .iter()
+        .map(|extension| {
+            let mut compressed_path = path.clone().into_os_string();
+            compressed_path.push(".");
+            compressed_path.push(extension);
+            PathBuf::from(compressed_path)
+        })
+        .find(|compressed_path| compressed_path.exists())
+        .unwrap_or(path)
+}
+
+// Transparently decompresses the input file. The time spent in the decoder is tracked separately,
+// such that it can be reported independently of the parsing time.
+pub struct InputReader {
+    inner: Box<dyn Read>,
+    compression: Compression,
+    decompression_time: Rc<Cell<Duration>>,
+}
+
+impl InputReader {
+    pub fn open(path: &Path) -> Result<Self, BenchmarkError> {
+        let compression = Compression::of_file(path)?;
+        let file = BufReader::new(File::open(path).map_err(|e| BenchmarkError::input(path, e))?);
+
+        let inner: Box<dyn Read> = match compression {
+            Compression::None => Box::new(file),
+            Compression::Gzip => Box::new(flate2::bufread::MultiGzDecoder::new(file)),
+            Compression::Zstd => Box::new(
+                zstd::Decoder::with_buffer(file).map_err(|e| BenchmarkError::input(path, e))?,
+            ),
+        };
+
+        Ok(Self {
+            inner,
+            compression,
+            decompression_time: Rc::new(Cell::new(Duration::ZERO)),
+        })
+    }
+
+    // can still be queried after the reader was moved into a parser
+    pub fn decompression_timer(&self) -> DecompressionTimer {
+        DecompressionTimer {
+            compression: self.compression,
+            decompression_time: Rc::clone(&self.decompression_time),
+        }
+    }
+}
+
+impl Read for InputReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.compression == Compression::None {
+            return self.inner.read(buf);
+        }
+
+        let start = Instant::now();
+        let result = self.inner.read(buf);
+        self.decompression_time
+            .set(self.decompression_time.get() + start.elapsed());
+
+        result
+    }
+}
+
+#[derive(Clone)]
+pub struct DecompressionTimer {
+    compression: Compression,
+    decompression_time: Rc<Cell<Duration>>,
+}
+
+impl DecompressionTimer {
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.decompression_time.get()
+    }
+}
@@ -0,0 +1,38 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+// The libraries use all kinds of different error types, so their errors are stored as messages.
+#[derive(Debug, thiserror::Error)]
+pub enum BenchmarkError {
+    #[error("invalid input {}: {message}", path.display())]
+    Input { path: PathBuf, message: String },
+
+    #[error("construction of the index failed: {0}")]
+    Construction(String),
+
+    #[error("file IO at {} failed: {message}", path.display())]
+    Io { path: PathBuf, message: String },
+
+    #[error("unsupported configuration: {0}")]
+    Unsupported(String),
+}
+
+impl BenchmarkError {
+    pub fn input(path: &Path, error: impl Display) -> Self {
+        Self::Input {
+            path: path.to_path_buf(),
+            message: error.to_string(),
+        }
+    }
+
+    pub fn construction(error: impl Display) -> Self {
+        Self::Construction(error.to_string())
+    }
+
+    pub fn io(path: &Path, error: impl Display) -> Self {
+        Self::Io {
+            path: path.to_path_buf(),
+            message: error.to_string(),
+        }
+    }
+}
@@ -1,75 +1,277 @@
-use crate::Config;
-use crate::common_interface::BenchmarkFmIndex;
+use crate::common_interface::{BenchmarkFmIndex, Capabilities, FileIoSupport, HitPosition};
+use crate::error::BenchmarkError;
+use crate::input::Texts;
+use crate::registry::{LibraryInfo, Variant};
+use crate::{Alphabet, Config};
 
-use fm_index::Search;
+use fm_index::{MatchWithLocate, Search};
 
 // Supports multiple different FM-Index variants.
 pub type FMIndexCrateSingleFmIndex = fm_index::FMIndexWithLocate<u8>;
 pub type FMIndexCrateMultiFmIndex = fm_index::FMIndexMultiPiecesWithLocate<u8>;
 
+pub const LIBRARY: LibraryInfo = LibraryInfo {
+    crate_name: "fm-index",
+    url: "https://github.com/ajalab/fm-index",
+    variants: &[
+        Variant {
+            name: "fm-index-single",
+            display_name: "fmindex single text",
+            color: "darkkhaki",
+            color_with_threads: "darkkhaki",
+            capabilities: FMIndexCrateSingleFmIndex::CAPABILITIES,
+            run_benchmark: FMIndexCrateSingleFmIndex::run_benchmark,
+        },
+        Variant {
+            name: "fm-index-multi",
+            display_name: "fmindex multi text",
+            color: "olive",
+            color_with_threads: "olive",
+            capabilities: FMIndexCrateMultiFmIndex::CAPABILITIES,
+            run_benchmark: FMIndexCrateMultiFmIndex::run_benchmark,
+        },
+    ],
+};
+
 impl BenchmarkFmIndex for FMIndexCrateMultiFmIndex {
     type IndexRef<'a> = &'a Self;
+    type Cursor<'a> = fm_index::FMIndexMultiPiecesSearchWithLocate<'a, u8>;
+
+    const CAPABILITIES: Capabilities = Capabilities {
+        multiple_texts: true,
+        file_io: FileIoSupport::Unsupported,
+        file_io_mmap: false,
+        multithreaded_construction: false,
+        locate: true,
+        cursor: true,
+        reads_texts_from_file: false,
+        alphabets: &[Alphabet::Dna, Alphabet::Protein, Alphabet::Bytes],
+    };
 
-    fn construct_for_benchmark(config: &Config, texts: Option<Vec<Vec<u8>>>) -> Self {
+    fn construct_for_benchmark(
+        config: &Config,
+        texts: Option<Texts>,
+    ) -> Result<Self, BenchmarkError> {
         let text: Vec<_> = texts
             .unwrap()
-            .into_iter()
-            .flat_map(|mut t| {
-                t.push(0);
-                t
-            })
+            .iter()
+            .flat_map(|t| t.iter().copied().chain([0]))
             .collect();
         let text = fm_index::Text::new(text);
 
-        assert!(config.suffix_array_sampling_rate.is_power_of_two());
-        let sampling_level = config.suffix_array_sampling_rate.ilog2() as usize;
+        let sampling_level = sampling_level(config)?;
 
         // let index = fm_index::FMIndexWithLocate::new(&text, sampling_level).unwrap(); <-- didn't improve memory usage
-        fm_index::FMIndexMultiPiecesWithLocate::new(&text, sampling_level).unwrap()
+        fm_index::FMIndexMultiPiecesWithLocate::new(&text, sampling_level)
+            .map_err(BenchmarkError::construction)
     }
 
-    fn as_stub_for_benchmark<'a>(&'a self) -> Self::IndexRef<'a> {
-        self
+    fn as_stub_for_benchmark<'a>(&'a self) -> Result<Self::IndexRef<'a>, BenchmarkError> {
+        Ok(self)
     }
 
     fn count_for_benchmark<'a>(index: &Self::IndexRef<'a>, query: &[u8]) -> usize {
         index.search(query).count()
     }
 
+    // the positions of the matches are located lazily and not resolved here, as in the earlier results
     fn count_via_locate_for_benchmark<'a>(index: &Self::IndexRef<'a>, query: &[u8]) -> usize {
         index.search(query).iter_matches().count()
     }
+
+    fn locate_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+        hits: &mut Vec<HitPosition>,
+    ) {
+        hits.extend(
+            index
+                .search(query)
+                .iter_matches()
+                .map(|m| HitPosition::Concatenated {
+                    position: m.locate(),
+                    separator_len: 1,
+                }),
+        );
+    }
+
+    fn locate_first_k_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+        k: usize,
+    ) -> usize {
+        index.search(query).iter_matches().take(k).count()
+    }
+
+    fn cursor_for_benchmark<'a>(index: &Self::IndexRef<'a>) -> Self::Cursor<'a> {
+        index.search(b"")
+    }
+
+    // the library copies the whole query for every refinement
+    fn extend_cursor_front_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+        symbol: u8,
+    ) -> Self::Cursor<'a> {
+        cursor.search([symbol])
+    }
+
+    fn search_cursor_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+    ) -> Self::Cursor<'a> {
+        index.search(query)
+    }
+
+    fn cursor_count_via_locate_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+    ) -> usize {
+        cursor.iter_matches().count()
+    }
+
+    fn cursor_count_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+    ) -> usize {
+        cursor.count()
+    }
+
+    fn cursor_locate_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+        hits: &mut Vec<HitPosition>,
+    ) {
+        hits.extend(cursor.iter_matches().map(|m| HitPosition::Concatenated {
+            position: m.locate(),
+            separator_len: 1,
+        }));
+    }
 }
 
 impl BenchmarkFmIndex for FMIndexCrateSingleFmIndex {
     type IndexRef<'a> = &'a Self;
+    type Cursor<'a> = fm_index::FMIndexSearchWithLocate<'a, u8>;
+
+    const CAPABILITIES: Capabilities = Capabilities {
+        multiple_texts: false,
+        file_io: FileIoSupport::Unsupported,
+        file_io_mmap: false,
+        multithreaded_construction: false,
+        locate: true,
+        cursor: true,
+        reads_texts_from_file: false,
+        alphabets: &[Alphabet::Dna, Alphabet::Protein, Alphabet::Bytes],
+    };
 
-    fn construct_for_benchmark(config: &Config, texts: Option<Vec<Vec<u8>>>) -> Self {
+    fn construct_for_benchmark(
+        config: &Config,
+        texts: Option<Texts>,
+    ) -> Result<Self, BenchmarkError> {
         let text: Vec<_> = texts
             .unwrap()
-            .into_iter()
-            .flat_map(|mut t| {
-                t.push(0);
-                t
-            })
+            .iter()
+            .flat_map(|t| t.iter().copied().chain([0]))
             .collect();
         let text = fm_index::Text::new(text);
 
-        assert!(config.suffix_array_sampling_rate.is_power_of_two());
-        let sampling_level = config.suffix_array_sampling_rate.ilog2() as usize;
+        let sampling_level = sampling_level(config)?;
 
-        fm_index::FMIndexWithLocate::new(&text, sampling_level).unwrap()
+        fm_index::FMIndexWithLocate::new(&text, sampling_level)
+            .map_err(BenchmarkError::construction)
     }
 
-    fn as_stub_for_benchmark<'a>(&'a self) -> Self::IndexRef<'a> {
-        self
+    fn as_stub_for_benchmark<'a>(&'a self) -> Result<Self::IndexRef<'a>, BenchmarkError> {
+        Ok(self)
     }
 
     fn count_for_benchmark<'a>(index: &Self::IndexRef<'a>, query: &[u8]) -> usize {
         index.search(query).count()
     }
 
+    // the positions of the matches are located lazily and not resolved here, as in the earlier results
     fn count_via_locate_for_benchmark<'a>(index: &Self::IndexRef<'a>, query: &[u8]) -> usize {
         index.search(query).iter_matches().count()
     }
+
+    fn locate_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+        hits: &mut Vec<HitPosition>,
+    ) {
+        hits.extend(
+            index
+                .search(query)
+                .iter_matches()
+                .map(|m| HitPosition::Concatenated {
+                    position: m.locate(),
+                    separator_len: 1,
+                }),
+        );
+    }
+
+    fn locate_first_k_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+        k: usize,
+    ) -> usize {
+        index.search(query).iter_matches().take(k).count()
+    }
+
+    fn cursor_for_benchmark<'a>(index: &Self::IndexRef<'a>) -> Self::Cursor<'a> {
+        index.search(b"")
+    }
+
+    // the library copies the whole query for every refinement
+    fn extend_cursor_front_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+        symbol: u8,
+    ) -> Self::Cursor<'a> {
+        cursor.search([symbol])
+    }
+
+    fn search_cursor_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+    ) -> Self::Cursor<'a> {
+        index.search(query)
+    }
+
+    fn cursor_count_via_locate_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+    ) -> usize {
+        cursor.iter_matches().count()
+    }
+
+    fn cursor_count_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+    ) -> usize {
+        cursor.count()
+    }
+
+    fn cursor_locate_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+        hits: &mut Vec<HitPosition>,
+    ) {
+        hits.extend(cursor.iter_matches().map(|m| HitPosition::Concatenated {
+            position: m.locate(),
+            separator_len: 1,
+        }));
+    }
+}
+
+// the fm-index crate only supports sampling rates that are powers of two
+fn sampling_level(config: &Config) -> Result<usize, BenchmarkError> {
+    if config.suffix_array_sampling_rate.is_power_of_two() {
+        Ok(config.suffix_array_sampling_rate.ilog2() as usize)
+    } else {
+        Err(BenchmarkError::Unsupported(format!(
+            "fm-index only supports suffix array sampling rates that are powers of two, got {}",
+            config.suffix_array_sampling_rate
+        )))
+    }
 }
@@ -1,43 +1,118 @@
 use std::path::Path;
 
-use crate::common_interface::BenchmarkFmIndex;
-use crate::{Config, ExtraBuildArg};
+use crate::common_interface::{
+    BenchmarkFmIndex, Capabilities, FileIoSupport, HitPosition, run_benchmark_for_text_size,
+};
+use crate::error::BenchmarkError;
+use crate::input::{Queries, Texts};
+use crate::registry::{LibraryInfo, Variant};
+use crate::{Alphabet, Config, ExtraBuildArg};
 use genedex::text_with_rank_support::TextWithRankSupport;
-use genedex::{FmIndex, FmIndexConfig, IndexStorage, PerformancePriority, alphabet};
+use genedex::{
+    Cursor, FmIndex, FmIndexCondensed64, FmIndexCondensed512, FmIndexConfig, FmIndexFlat64,
+    IndexStorage, PerformancePriority, alphabet,
+};
 
 pub type GenedexFMIndex<I, R> = FmIndex<I, R>;
 
+pub const LIBRARY: LibraryInfo = LibraryInfo {
+    crate_name: "genedex",
+    url: "https://github.com/feldroop/genedex",
+    variants: &[
+        Variant {
+            name: "genedex-flat64",
+            display_name: "genedex flat64",
+            color: "blue",
+            color_with_threads: "cornflowerblue",
+            capabilities: FmIndexFlat64::<u32>::CAPABILITIES,
+            run_benchmark: run_benchmark_for_text_size::<
+                FmIndexFlat64<i32>,
+                FmIndexFlat64<u32>,
+                FmIndexFlat64<i64>,
+            >,
+        },
+        Variant {
+            name: "genedex-cond64",
+            display_name: "genedex cond64",
+            color: "blueviolet",
+            color_with_threads: "violet",
+            capabilities: FmIndexCondensed64::<u32>::CAPABILITIES,
+            run_benchmark: run_benchmark_for_text_size::<
+                FmIndexCondensed64<i32>,
+                FmIndexCondensed64<u32>,
+                FmIndexCondensed64<i64>,
+            >,
+        },
+        Variant {
+            name: "genedex-cond512",
+            display_name: "genedex cond512",
+            color: "mediumvioletred",
+            color_with_threads: "palevioletred",
+            capabilities: FmIndexCondensed512::<u32>::CAPABILITIES,
+            run_benchmark: run_benchmark_for_text_size::<
+                FmIndexCondensed512<i32>,
+                FmIndexCondensed512<u32>,
+                FmIndexCondensed512<i64>,
+            >,
+        },
+    ],
+};
+
 impl<I: IndexStorage, R: TextWithRankSupport<I>> BenchmarkFmIndex for GenedexFMIndex<I, R> {
     type IndexRef<'a> = &'a Self;
+    type Cursor<'a>
+        = Cursor<'a, I, R>
+    where
+        Self: 'a;
+
+    const CAPABILITIES: Capabilities = Capabilities {
+        multiple_texts: true,
+        file_io: FileIoSupport::Fast,
+        file_io_mmap: false,
+        multithreaded_construction: true,
+        locate: true,
+        cursor: true,
+        reads_texts_from_file: false,
+        alphabets: &[Alphabet::Dna, Alphabet::Protein, Alphabet::Bytes],
+    };
 
-    fn construct_for_benchmark(config: &Config, texts: Option<Vec<Vec<u8>>>) -> Self {
+    fn construct_for_benchmark(
+        config: &Config,
+        texts: Option<Texts>,
+    ) -> Result<Self, BenchmarkError> {
         let performance_priority = match config.extra_build_arg {
             Some(ExtraBuildArg::LowMemory) => PerformancePriority::LowMemory,
             Some(ExtraBuildArg::MediumMemory) => PerformancePriority::Balanced,
             _ => PerformancePriority::HighSpeed,
         };
 
-        FmIndexConfig::<I, R>::new()
-            .lookup_table_depth(config.depth_of_lookup_table)
+        // the wildcard is not searchable, like the N of the DNA alphabet
+        let alphabet = match config.alphabet() {
+            Alphabet::Dna => alphabet::ascii_dna_with_n(),
+            Alphabet::Protein => {
+                alphabet::Alphabet::from_io_symbols(config.alphabet().symbols(), 1)
+            }
+            Alphabet::Bytes => alphabet::Alphabet::from_io_symbols(config.alphabet().symbols(), 0),
+        };
+
+        Ok(FmIndexConfig::<I, R>::new()
+            .lookup_table_depth(config.lookup_table_depth())
             .suffix_array_sampling_rate(config.suffix_array_sampling_rate)
             .construction_performance_priority(performance_priority)
-            .construct_index(texts.unwrap(), alphabet::ascii_dna_with_n())
+            .construct_index(texts.unwrap().iter(), alphabet))
     }
 
-    fn supports_file_io_for_benchmark(_config: &Config) -> bool {
-        true
+    fn write_to_file_for_benchmark(self, path: &Path) -> Result<(), BenchmarkError> {
+        self.save_to_file(path)
+            .map_err(|e| BenchmarkError::io(path, e))
     }
 
-    fn write_to_file_for_benchmark(self, path: &Path) {
-        self.save_to_file(path).unwrap();
+    fn load_from_file_for_benchmark(path: &Path) -> Result<Self, BenchmarkError> {
+        Self::load_from_file(path).map_err(|e| BenchmarkError::io(path, e))
     }
 
-    fn load_from_file_for_benchmark(path: &Path) -> Self {
-        Self::load_from_file(path).unwrap()
-    }
-
-    fn as_stub_for_benchmark<'a>(&'a self) -> Self::IndexRef<'a> {
-        self
+    fn as_stub_for_benchmark<'a>(&'a self) -> Result<Self::IndexRef<'a>, BenchmarkError> {
+        Ok(self)
     }
 
     fn count_for_benchmark<'a>(index: &Self::IndexRef<'a>, query: &[u8]) -> usize {
@@ -48,17 +123,111 @@ impl<I: IndexStorage, R: TextWithRankSupport<I>> BenchmarkFmIndex for GenedexFMI
         index.locate(query).count()
     }
 
-    fn count_many_for_benchmark<'a>(index: &Self::IndexRef<'a>, queries: &[Vec<u8>]) -> usize {
-        index.count_many(queries.iter().map(|q| q.as_slice())).sum()
+    fn locate_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+        hits: &mut Vec<HitPosition>,
+    ) {
+        hits.extend(index.locate(query).map(|hit| HitPosition::InText {
+            text_id: hit.text_id,
+            position: hit.position,
+        }));
+    }
+
+    fn locate_first_k_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+        k: usize,
+    ) -> usize {
+        index.locate(query).take(k).count()
+    }
+
+    fn cursor_for_benchmark<'a>(index: &Self::IndexRef<'a>) -> Self::Cursor<'a> {
+        index.cursor_empty()
+    }
+
+    fn extend_cursor_front_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+        symbol: u8,
+    ) -> Self::Cursor<'a> {
+        let mut cursor = *cursor;
+        cursor.extend_query_front(symbol);
+        cursor
+    }
+
+    // uses the lookup table like count
+    fn search_cursor_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+    ) -> Self::Cursor<'a> {
+        index.cursor_for_query(query)
+    }
+
+    fn cursor_count_via_locate_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+    ) -> usize {
+        cursor.locate().count()
+    }
+
+    fn cursor_count_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+    ) -> usize {
+        cursor.count()
+    }
+
+    fn cursor_locate_for_benchmark<'a>(
+        _index: &Self::IndexRef<'a>,
+        cursor: &Self::Cursor<'a>,
+        hits: &mut Vec<HitPosition>,
+    ) {
+        hits.extend(cursor.locate().map(|hit| HitPosition::InText {
+            text_id: hit.text_id,
+            position: hit.position,
+        }));
+    }
+
+    fn count_many_for_benchmark<'a>(index: &Self::IndexRef<'a>, queries: &Queries) -> usize {
+        index.count_many(queries.iter()).sum()
     }
 
     fn count_many_via_locate_for_benchmark<'a>(
         index: &Self::IndexRef<'a>,
-        queries: &[Vec<u8>],
+        queries: &Queries,
     ) -> usize {
         index
-            .locate_many(queries.iter().map(|q| q.as_slice()))
+            .locate_many(queries.iter())
             .map(|hits| hits.count())
             .sum()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::config_from_args;
+
+    #[test]
+    fn construct_byte_alphabet_index_with_default_config() {
+        let config = config_from_args(&["genedex-flat64", "-i", "english"]);
+
+        let mut texts = Texts::new();
+        texts.push(b"Hello, world!");
+        texts.push(b"the whole wide world");
+
+        let index = FmIndexFlat64::<u32>::construct_for_benchmark(&config, Some(texts)).unwrap();
+        let index = index.as_stub_for_benchmark().unwrap();
+
+        assert_eq!(
+            FmIndexFlat64::<u32>::count_for_benchmark(&index, b"world"),
+            2
+        );
+        assert_eq!(
+            FmIndexFlat64::<u32>::count_for_benchmark(&index, b"World"),
+            0
+        );
+        assert_eq!(FmIndexFlat64::<u32>::count_for_benchmark(&index, b"o"), 4);
+    }
+}
@@ -0,0 +1,210 @@
+use std::time::Duration;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+// the buckets are the queries with at most this many hits, and one more bucket for all other queries
+const BUCKET_MAX_HITS: [usize; 6] = [0, 1, 10, 100, 1_000, 10_000];
+const NUM_TOP_REPETITIVE_QUERIES: usize = 10;
+
+// The distribution of the number of hits per query, measured in a separate pass after the search benchmark.
+// The time of the backward search (count) and the time of locate are measured per query, such that the share
+// of the suffix array lookups in the locate time can be attributed to the repetitive queries.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HitStats {
+    pub num_queries: usize,
+    pub num_zero_hit_queries: usize,
+    pub max_hits: usize,
+    pub buckets: Vec<HitCountBucket>,
+    // sorted by the number of hits, descending
+    pub top_repetitive_queries: Vec<(String, usize)>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HitCountBucket {
+    // None for the last bucket
+    pub max_hits: Option<usize>,
+    pub num_queries: usize,
+    pub total_hits: usize,
+    pub count_time_secs: f64,
+    // None, if the library doesn't support locate
+    pub locate_time_secs: Option<f64>,
+}
+
+impl HitStats {
+    pub fn new(supports_locate: bool) -> Self {
+        let buckets = BUCKET_MAX_HITS
+            .iter()
+            .map(|&max_hits| Some(max_hits))
+            .chain([None])
+            .map(|max_hits| HitCountBucket {
+                max_hits,
+                num_queries: 0,
+                total_hits: 0,
+                count_time_secs: 0.0,
+                locate_time_secs: supports_locate.then_some(0.0),
+            })
+            .collect();
+
+        Self {
+            num_queries: 0,
+            num_zero_hit_queries: 0,
+            max_hits: 0,
+            buckets,
+            top_repetitive_queries: Vec::new(),
+        }
+    }
+
+    pub fn add_query(
+        &mut self,
+        query: &[u8],
+        num_hits: usize,
+        count_time: Duration,
+        locate_time: Option<Duration>,
+    ) {
+        self.num_queries += 1;
+        self.max_hits = self.max_hits.max(num_hits);
+        if num_hits == 0 {
+            self.num_zero_hit_queries += 1;
+        }
+
+        let bucket_index = BUCKET_MAX_HITS.partition_point(|&max_hits| max_hits < num_hits);
+        let bucket = &mut self.buckets[bucket_index];
+        bucket.num_queries += 1;
+        bucket.total_hits += num_hits;
+        bucket.count_time_secs += count_time.as_secs_f64();
+        if let (Some(locate_time_secs), Some(locate_time)) =
+            (bucket.locate_time_secs.as_mut(), locate_time)
+        {
+            *locate_time_secs += locate_time.as_secs_f64();
+        }
+
+        self.add_to_top_repetitive_queries(query, num_hits);
+    }
+
+    fn add_to_top_repetitive_queries(&mut self, query: &[u8], num_hits: usize) {
+        if num_hits == 0
+            || (self.top_repetitive_queries.len() == NUM_TOP_REPETITIVE_QUERIES
+                && self.top_repetitive_queries.last().unwrap().1 >= num_hits)
+        {
+            return;
+        }
+
+        let index = self
+            .top_repetitive_queries
+            .partition_point(|&(_, hits)| hits >= num_hits);
+        self.top_repetitive_queries.insert(
+            index,
+            (String::from_utf8_lossy(query).into_owned(), num_hits),
+        );
+        self.top_repetitive_queries
+            .truncate(NUM_TOP_REPETITIVE_QUERIES);
+    }
+
+    pub fn log(&self) {
+        info!(
+            "Queries without hits: {:.1}%, maximum number of hits of a query: {}",
+            self.num_zero_hit_queries as f64 / self.num_queries.max(1) as f64 * 100.0,
+            self.max_hits
+        );
+
+        let mut min_hits = 0;
+        for bucket in &self.buckets {
+            let range = match bucket.max_hits {
+                Some(max_hits) if max_hits == min_hits => format!("{min_hits}"),
+                Some(max_hits) => format!("{min_hits}-{max_hits}"),
+                None => format!(">={min_hits}"),
+            };
+
+            info!(
+                "Queries with {range} hits: {}, total hits: {}, count time: {:.3} seconds, locate time: {}",
+                bucket.num_queries,
+                bucket.total_hits,
+                bucket.count_time_secs,
+                bucket
+                    .locate_time_secs
+                    .map_or_else(|| String::from("-"), |secs| format!("{secs:.3} seconds")),
+            );
+
+            min_hits = bucket.max_hits.map_or(min_hits, |max_hits| max_hits + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit_stats(hit_counts: &[usize], supports_locate: bool) -> HitStats {
+        let mut hit_stats = HitStats::new(supports_locate);
+        for (i, &num_hits) in hit_counts.iter().enumerate() {
+            hit_stats.add_query(
+                i.to_string().as_bytes(),
+                num_hits,
+                Duration::from_millis(1),
+                supports_locate.then_some(Duration::from_millis(2)),
+            );
+        }
+        hit_stats
+    }
+
+    #[test]
+    fn bucketing() {
+        let hit_stats = hit_stats(&[0, 1, 2, 10, 11, 100, 5_000, 10_000, 10_001, 0], true);
+
+        assert_eq!(hit_stats.num_queries, 10);
+        assert_eq!(hit_stats.num_zero_hit_queries, 2);
+        assert_eq!(hit_stats.max_hits, 10_001);
+
+        let buckets: Vec<_> = hit_stats
+            .buckets
+            .iter()
+            .map(|bucket| (bucket.max_hits, bucket.num_queries, bucket.total_hits))
+            .collect();
+        assert_eq!(
+            buckets,
+            [
+                (Some(0), 2, 0),
+                (Some(1), 1, 1),
+                (Some(10), 2, 12),
+                (Some(100), 2, 111),
+                (Some(1_000), 0, 0),
+                (Some(10_000), 2, 15_000),
+                (None, 1, 10_001),
+            ]
+        );
+
+        let last_bucket = hit_stats.buckets.last().unwrap();
+        assert!((last_bucket.count_time_secs - 0.001).abs() < 1e-9);
+        assert!((last_bucket.locate_time_secs.unwrap() - 0.002).abs() < 1e-9);
+    }
+
+    #[test]
+    fn no_locate_time_without_locate() {
+        let hit_stats = hit_stats(&[3, 0], false);
+
+        assert!(
+            hit_stats
+                .buckets
+                .iter()
+                .all(|bucket| bucket.locate_time_secs.is_none())
+        );
+    }
+
+    #[test]
+    fn top_repetitive_queries() {
+        let hit_counts: Vec<_> = (0..15).map(|i| (i * 7) % 15).collect();
+        let hit_stats = hit_stats(&hit_counts, false);
+
+        let top_hits: Vec<_> = hit_stats
+            .top_repetitive_queries
+            .iter()
+            .map(|(_, num_hits)| *num_hits)
+            .collect();
+        assert_eq!(top_hits, (5..15).rev().collect::<Vec<_>>());
+
+        // the names are the indices of the queries
+        let (query, num_hits) = &hit_stats.top_repetitive_queries[0];
+        assert_eq!(hit_counts[query.parse::<usize>().unwrap()], *num_hits);
+    }
+}
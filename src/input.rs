@@ -0,0 +1,1159 @@
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, Read};
+use std::ops::{Deref, Range};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use bio::io::fasta::FastaRead;
+use bio::io::fastq::FastqRead;
+use log::info;
+use memmap2::Mmap;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::common_interface::process_current_memory_usage_mb;
+use crate::compression::{Compression, DecompressionTimer, InputReader};
+use crate::error::BenchmarkError;
+use crate::input_cache::InputCache;
+use crate::{Alphabet, Config, DegeneratePolicy, QueryOrder, TextSplit};
+
+// used when the queries are sampled from the texts and the number, length or seed is not given
+const DEFAULT_NUM_SAMPLED_QUERIES: usize = 100_000;
+const DEFAULT_LENGTH_OF_SAMPLED_QUERIES: usize = 20;
+const DEFAULT_QUERY_SAMPLING_SEED: u64 = 42;
+
+// stored in the results to make the hit counts comparable
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct DegenerateSymbolStats {
+    pub policy: DegeneratePolicy,
+    pub num_affected_sequences: usize,
+}
+
+// The names of the records are only used for the input cache. Plain texts don't have names, they are empty.
+#[derive(Default)]
+struct Records {
+    names: Sequences,
+    seqs: Sequences,
+}
+
+impl Records {
+    fn push(&mut self, name: &[u8], seq: &[u8]) {
+        self.names.push(name);
+        self.seqs.push(seq);
+    }
+
+    fn select(&self, indices: impl IntoIterator<Item = usize>) -> Self {
+        let mut records = Self::default();
+        for i in indices {
+            records.push(self.names.get(i), self.seqs.get(i));
+        }
+        records
+    }
+}
+
+// All sequences in one contiguous buffer, which avoids the overhead of an allocation per sequence. The records are
+// pushed directly into the buffer while they are parsed.
+pub struct Sequences {
+    symbols: Symbols,
+    // sequence i is symbols[offsets[i]..offsets[i + 1]]
+    offsets: Vec<usize>,
+}
+
+pub type Texts = Sequences;
+pub type Queries = Sequences;
+
+// Sequences read from the input cache are used directly from the memory map of the cache file. They are only copied,
+// if they are modified.
+enum Symbols {
+    Owned(Vec<u8>),
+    Mapped(Mmap, Range<usize>),
+}
+
+impl Symbols {
+    fn to_mut(&mut self) -> &mut Vec<u8> {
+        if let Symbols::Mapped(mmap, range) = self {
+            *self = Symbols::Owned(mmap[range.clone()].to_vec());
+        }
+
+        match self {
+            Symbols::Owned(symbols) => symbols,
+            Symbols::Mapped(..) => unreachable!(),
+        }
+    }
+}
+
+impl Deref for Symbols {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Symbols::Owned(symbols) => symbols,
+            Symbols::Mapped(mmap, range) => &mmap[range.clone()],
+        }
+    }
+}
+
+impl Sequences {
+    pub fn new() -> Self {
+        Self {
+            symbols: Symbols::Owned(Vec::new()),
+            offsets: vec![0],
+        }
+    }
+
+    // the lengths have to sum up to the length of the range
+    pub fn from_mmap(
+        mmap: Mmap,
+        range: Range<usize>,
+        lengths: impl Iterator<Item = usize>,
+    ) -> Self {
+        let mut offsets = vec![0];
+        offsets.extend(lengths.scan(0, |end, len| {
+            *end += len;
+            Some(*end)
+        }));
+        debug_assert_eq!(offsets.last(), Some(&range.len()));
+
+        Self {
+            symbols: Symbols::Mapped(mmap, range),
+            offsets,
+        }
+    }
+
+    pub fn push(&mut self, seq: &[u8]) {
+        let symbols = self.symbols.to_mut();
+        symbols.extend_from_slice(seq);
+        self.offsets.push(symbols.len());
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn get(&self, index: usize) -> &[u8] {
+        &self.symbols[self.offsets[index]..self.offsets[index + 1]]
+    }
+
+    // the sequences at the indices in the given order, copied into a new buffer
+    fn select(&self, indices: impl IntoIterator<Item = usize>) -> Self {
+        let mut seqs = Self::new();
+        for i in indices {
+            seqs.push(self.get(i));
+        }
+        seqs
+    }
+
+    // the queries stay in the same order, the target is reused to avoid allocations
+    pub fn reverse_complement_into(&self, target: &mut Queries) {
+        target.clear();
+
+        let target_symbols = target.symbols.to_mut();
+        for query in self.iter() {
+            target_symbols.extend(query.iter().rev().map(|&symbol| complement(symbol)));
+            target.offsets.push(target_symbols.len());
+        }
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &[u8]> + Clone {
+        self.offsets
+            .windows(2)
+            .map(|window| &self.symbols[window[0]..window[1]])
+    }
+
+    // the concatenation of all sequences
+    pub fn symbols(&self) -> &[u8] {
+        &self.symbols
+    }
+
+    // copies the symbols only if they are memory mapped
+    pub fn into_symbols(mut self) -> Vec<u8> {
+        std::mem::take(self.symbols.to_mut())
+    }
+
+    fn clear(&mut self) {
+        self.symbols.to_mut().clear();
+        self.offsets.truncate(1);
+    }
+}
+
+impl Default for Sequences {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// other symbols, like the wildcard N, are their own complement
+fn complement(symbol: u8) -> u8 {
+    match symbol {
+        b'A' => b'T',
+        b'C' => b'G',
+        b'G' => b'C',
+        b'T' => b'A',
+        _ => symbol,
+    }
+}
+
+pub fn read_texts(config: &Config) -> Result<(Texts, DegenerateSymbolStats), BenchmarkError> {
+    let policy = config.texts_degenerate_policy();
+
+    if let DegeneratePolicy::Expand(_) = policy {
+        return Err(BenchmarkError::Unsupported(String::from(
+            "degenerate symbols of the texts can't be expanded",
+        )));
+    }
+
+    let transformation = format!("texts-{}-{policy}-{}", config.alphabet(), config.text_split);
+
+    let texts = read_with_cache(
+        config,
+        "texts",
+        &config.input_texts.get_filepath(),
+        transformation,
+        policy,
+        || read_raw_texts(config),
+    )?;
+
+    info!(
+        "Current memory usage after reading texts: {:.1} MB",
+        process_current_memory_usage_mb()
+    );
+
+    Ok(texts)
+}
+
+fn read_raw_texts(config: &Config) -> Result<(Records, DecompressionTimer), BenchmarkError> {
+    let path = config.input_texts.get_filepath();
+
+    if config.alphabet() == Alphabet::Bytes {
+        return read_plain_texts(&path, config.text_split);
+    }
+
+    let input_reader = InputReader::open(&path)?;
+    let decompression_timer = input_reader.decompression_timer();
+    let reader = bio::io::fasta::Reader::new(input_reader);
+
+    let mut records = Records::default();
+
+    for record in reader.records() {
+        let record = record.map_err(|e| BenchmarkError::input(&path, e))?;
+        records.push(record.id().as_bytes(), record.seq());
+    }
+
+    Ok((records, decompression_timer))
+}
+
+// empty texts are skipped, because some of the libraries don't support them
+fn read_plain_texts(
+    path: &Path,
+    text_split: TextSplit,
+) -> Result<(Records, DecompressionTimer), BenchmarkError> {
+    let mut input_reader = InputReader::open(path)?;
+    let decompression_timer = input_reader.decompression_timer();
+
+    let mut content = Vec::new();
+    input_reader
+        .read_to_end(&mut content)
+        .map_err(|e| BenchmarkError::input(path, e))?;
+
+    let mut records = Records::default();
+
+    match text_split {
+        TextSplit::Whole => records.push(b"", &content),
+        TextSplit::Lines => {
+            for line in content
+                .split(|&symbol| symbol == b'\n')
+                .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+                .filter(|line| !line.is_empty())
+            {
+                records.push(b"", line);
+            }
+        }
+        TextSplit::Documents => {
+            for document in content
+                .split(|&symbol| symbol == b'\n')
+                .collect::<Vec<_>>()
+                .split(|line| line.is_empty() || *line == b"\r")
+                .filter(|document| !document.is_empty())
+            {
+                records.push(b"", &document.join(&b'\n'));
+            }
+        }
+    }
+
+    Ok((records, decompression_timer))
+}
+
+pub fn read_queries(config: &Config) -> Result<(Queries, DegenerateSymbolStats), BenchmarkError> {
+    let policy = config.queries_degenerate_policy();
+
+    let (queries, degenerate_symbol_stats) = match config.queries_path() {
+        Some(path) => {
+            let transformation = format!(
+                "queries-{}-{policy}-{:?}-{:?}-{:?}",
+                config.alphabet(),
+                config.num_queries_records,
+                config.length_of_queries,
+                config.query_seed
+            );
+
+            read_with_cache(config, "queries", &path, transformation, policy, || {
+                read_queries_from_file(&path, config)
+            })?
+        }
+        None => {
+            let transformation = format!(
+                "sampled-queries-{}-{policy}-{}-{:?}-{:?}-{}",
+                config.alphabet(),
+                config.text_split,
+                config.num_queries_records,
+                config.length_of_queries,
+                config.query_seed.unwrap_or(DEFAULT_QUERY_SAMPLING_SEED)
+            );
+
+            read_with_cache(
+                config,
+                "queries",
+                &config.input_texts.get_filepath(),
+                transformation,
+                policy,
+                || sample_queries_from_texts(config),
+            )?
+        }
+    };
+
+    // the input cache stores the queries in file order
+    let queries = order_queries(queries, config);
+
+    info!(
+        "Current memory usage after reading queries: {:.1} MB",
+        process_current_memory_usage_mb()
+    );
+
+    Ok((queries, degenerate_symbol_stats))
+}
+
+fn order_queries(queries: Queries, config: &Config) -> Queries {
+    let mut order: Vec<_> = (0..queries.len()).collect();
+
+    match config.query_order {
+        QueryOrder::File => return queries,
+        QueryOrder::Shuffled => {
+            let mut rng =
+                StdRng::seed_from_u64(config.query_seed.unwrap_or(DEFAULT_QUERY_SAMPLING_SEED));
+            order.shuffle(&mut rng);
+        }
+        QueryOrder::Sorted => order.sort_by_key(|&i| queries.get(i)),
+        QueryOrder::ReverseSorted => {
+            order.sort_by(|&a, &b| queries.get(a).iter().rev().cmp(queries.get(b).iter().rev()));
+        }
+        QueryOrder::Deduplicated => {
+            let mut distinct_queries = HashSet::new();
+            order.retain(|&i| distinct_queries.insert(queries.get(i)));
+
+            info!(
+                "Removed {} duplicate queries",
+                queries.len() - distinct_queries.len()
+            );
+        }
+    }
+
+    info!("Query order: {}", config.query_order);
+
+    queries.select(order)
+}
+
+// Reads the queries from the file in chunks during the search, like in a pipeline where the queries arrive
+// continuously. The input cache is not used.
+pub struct QueryStream {
+    reader: QueryRecordReader,
+    num_remaining_queries: usize,
+    alphabet: Alphabet,
+    policy: DegeneratePolicy,
+    chunk_size: usize,
+    num_affected_sequences: usize,
+}
+
+impl QueryStream {
+    pub fn open(config: &Config, chunk_size: usize) -> Result<Self, BenchmarkError> {
+        let Some(path) = config.queries_path() else {
+            return Err(BenchmarkError::Unsupported(String::from(
+                "queries sampled from the texts can't be streamed",
+            )));
+        };
+
+        if config.query_seed.is_some() {
+            return Err(BenchmarkError::Unsupported(String::from(
+                "the queries can't be sampled randomly, when they are streamed",
+            )));
+        }
+
+        let (reader, _) = QueryRecordReader::open(&path, config)?;
+
+        Ok(Self {
+            reader,
+            num_remaining_queries: config.num_queries_records.unwrap_or(usize::MAX),
+            alphabet: config.alphabet(),
+            policy: config.queries_degenerate_policy(),
+            chunk_size,
+            num_affected_sequences: 0,
+        })
+    }
+
+    // returns false, if all queries have been read
+    pub fn next_chunk(&mut self, chunk: &mut Queries) -> Result<bool, BenchmarkError> {
+        // the buffer of the chunk is reused
+        chunk.clear();
+        let mut records = Records {
+            names: Sequences::new(),
+            seqs: std::mem::take(chunk),
+        };
+
+        while records.seqs.len() < self.chunk_size.min(self.num_remaining_queries)
+            && let Some((_, seq)) = self.reader.next_record()?
+        {
+            records.push(b"", seq);
+        }
+        self.num_remaining_queries -= records.seqs.len();
+
+        if records.seqs.len() == 0 {
+            return Ok(false);
+        }
+
+        self.num_affected_sequences += transform_records(&mut records, self.alphabet, self.policy);
+        *chunk = records.seqs;
+
+        Ok(true)
+    }
+
+    pub fn degenerate_symbol_stats(&self) -> DegenerateSymbolStats {
+        DegenerateSymbolStats {
+            policy: self.policy,
+            num_affected_sequences: self.num_affected_sequences,
+        }
+    }
+}
+
+// Uses the input cache if it is enabled and valid. Otherwise, the records are read and transformed and the cache
+// is created, if it is enabled.
+fn read_with_cache(
+    config: &Config,
+    name: &str,
+    source_path: &Path,
+    transformation: String,
+    policy: DegeneratePolicy,
+    read_records: impl FnOnce() -> Result<(Records, DecompressionTimer), BenchmarkError>,
+) -> Result<(Sequences, DegenerateSymbolStats), BenchmarkError> {
+    let start = Instant::now();
+
+    let cache = if config.input_cache {
+        Some(InputCache::new(source_path, transformation)?)
+    } else {
+        None
+    };
+
+    if let Some(cache) = &cache
+        && let Some(cached_records) = cache.load()?
+    {
+        info!(
+            "Read {} {name} from the cache {}",
+            cached_records.seqs.len(),
+            cache.path().display()
+        );
+
+        if config.verbose {
+            info!(
+                "Reading time of the {name}: {:.2} seconds",
+                start.elapsed().as_secs_f64()
+            );
+        }
+
+        let degenerate_symbol_stats = DegenerateSymbolStats {
+            policy,
+            num_affected_sequences: cached_records.num_affected_sequences,
+        };
+
+        return Ok((cached_records.seqs, degenerate_symbol_stats));
+    }
+
+    let (mut records, decompression_timer) = read_records()?;
+
+    let degenerate_symbol_stats = transform_seqs(
+        &mut records,
+        name,
+        config.alphabet(),
+        policy,
+        config.verbose,
+    );
+
+    log_reading_time(name, start, &decompression_timer, config.verbose);
+
+    if let Some(cache) = &cache {
+        cache.store(
+            &records.names,
+            &records.seqs,
+            degenerate_symbol_stats.num_affected_sequences,
+        )?;
+        info!("Stored the {name} in the cache {}", cache.path().display());
+    }
+
+    Ok((records.seqs, degenerate_symbol_stats))
+}
+
+fn read_queries_from_file(
+    path: &Path,
+    config: &Config,
+) -> Result<(Records, DecompressionTimer), BenchmarkError> {
+    let (mut reader, decompression_timer) = QueryRecordReader::open(path, config)?;
+    let num_queries = config.num_queries_records.unwrap_or(usize::MAX);
+
+    let records = match config.query_seed {
+        None => {
+            let mut records = Records::default();
+            while records.seqs.len() < num_queries
+                && let Some((name, seq)) = reader.next_record()?
+            {
+                records.push(name, seq);
+            }
+            records
+        }
+        Some(seed) => {
+            let mut rng = StdRng::seed_from_u64(seed);
+            reservoir_sample(&mut reader, num_queries, &mut rng)?
+        }
+    };
+
+    Ok((records, decompression_timer))
+}
+
+// Samples uniformly from the whole file, instead of taking the first records, which are biased by the order of the
+// sequencing run. The sampled records are returned in file order, the order of the search is set by the query order.
+fn reservoir_sample(
+    reader: &mut QueryRecordReader,
+    sample_size: usize,
+    rng: &mut StdRng,
+) -> Result<Records, BenchmarkError> {
+    // The sampled records are appended to the buffer and replaced records stay in it, until it is compacted.
+    // reservoir[j] is the index of the j-th sampled record in the buffer. The buffer stays in file order.
+    let mut buffer = Records::default();
+    let mut reservoir = Vec::new();
+    let mut i = 0;
+
+    while let Some((name, seq)) = reader.next_record()? {
+        let j = if i < sample_size {
+            reservoir.push(0);
+            i
+        } else {
+            rng.gen_range(0..=i)
+        };
+
+        if j < sample_size {
+            reservoir[j] = buffer.seqs.len();
+            buffer.push(name, seq);
+
+            if buffer.seqs.len() >= 2 * reservoir.len() {
+                let mut slots: Vec<_> = (0..reservoir.len()).collect();
+                slots.sort_unstable_by_key(|&j| reservoir[j]);
+
+                buffer = buffer.select(slots.iter().map(|&j| reservoir[j]));
+                for (index_in_buffer, j) in slots.into_iter().enumerate() {
+                    reservoir[j] = index_in_buffer;
+                }
+            }
+        }
+
+        i += 1;
+    }
+
+    reservoir.sort_unstable();
+
+    Ok(buffer.select(reservoir))
+}
+
+// Reads FASTA or FASTQ records, or one query per line for the byte alphabet. The records are read into reused
+// buffers, such that they can be pushed into the contiguous buffers without an allocation per record. The length of
+// the queries is limited as configured.
+struct QueryRecordReader {
+    format: QueryFileFormat,
+    path: PathBuf,
+    length_of_queries: Option<usize>,
+}
+
+type NamedSeq<'a> = (&'a [u8], &'a [u8]);
+
+enum QueryFileFormat {
+    Fasta(
+        bio::io::fasta::Reader<BufReader<InputReader>>,
+        bio::io::fasta::Record,
+    ),
+    Fastq(
+        bio::io::fastq::Reader<BufReader<InputReader>>,
+        bio::io::fastq::Record,
+    ),
+    Lines(BufReader<InputReader>, Vec<u8>),
+}
+
+impl QueryRecordReader {
+    fn open(path: &Path, config: &Config) -> Result<(Self, DecompressionTimer), BenchmarkError> {
+        let input_reader = InputReader::open(path)?;
+        let decompression_timer = input_reader.decompression_timer();
+
+        let format = if config.alphabet() == Alphabet::Bytes {
+            QueryFileFormat::Lines(BufReader::new(input_reader), Vec::new())
+        } else if is_fasta_file(path)? {
+            QueryFileFormat::Fasta(
+                bio::io::fasta::Reader::new(input_reader),
+                bio::io::fasta::Record::new(),
+            )
+        } else {
+            QueryFileFormat::Fastq(
+                bio::io::fastq::Reader::new(input_reader),
+                bio::io::fastq::Record::new(),
+            )
+        };
+
+        let reader = Self {
+            format,
+            path: path.to_path_buf(),
+            length_of_queries: config.length_of_queries,
+        };
+
+        Ok((reader, decompression_timer))
+    }
+
+    // returns the name and the sequence of the next record, they are only valid until the next call
+    fn next_record(&mut self) -> Result<Option<NamedSeq<'_>>, BenchmarkError> {
+        let path = &self.path;
+
+        let (name, seq) = match &mut self.format {
+            QueryFileFormat::Fasta(reader, record) => {
+                reader
+                    .read(record)
+                    .map_err(|e| BenchmarkError::input(path, e))?;
+                if record.is_empty() {
+                    return Ok(None);
+                }
+                (record.id().as_bytes(), record.seq())
+            }
+            QueryFileFormat::Fastq(reader, record) => {
+                reader
+                    .read(record)
+                    .map_err(|e| BenchmarkError::input(path, e))?;
+                if record.is_empty() {
+                    return Ok(None);
+                }
+                (record.id().as_bytes(), record.seq())
+            }
+            // empty lines are skipped like for the plain texts
+            QueryFileFormat::Lines(reader, line) => {
+                loop {
+                    line.clear();
+                    let num_bytes = reader
+                        .read_until(b'\n', line)
+                        .map_err(|e| BenchmarkError::input(path, e))?;
+                    if num_bytes == 0 {
+                        return Ok(None);
+                    }
+
+                    for line_ending in [b'\n', b'\r'] {
+                        if line.last() == Some(&line_ending) {
+                            line.pop();
+                        }
+                    }
+                    if !line.is_empty() {
+                        break;
+                    }
+                }
+                (&b""[..], &line[..])
+            }
+        };
+
+        let len = self
+            .length_of_queries
+            .map_or(seq.len(), |l| l.min(seq.len()));
+
+        Ok(Some((name, &seq[..len])))
+    }
+}
+
+// Samples substrings of the texts uniformly at random. The texts are read again, because the index
+// construction takes ownership of them.
+fn sample_queries_from_texts(
+    config: &Config,
+) -> Result<(Records, DecompressionTimer), BenchmarkError> {
+    let num_queries = config
+        .num_queries_records
+        .unwrap_or(DEFAULT_NUM_SAMPLED_QUERIES);
+    let length_of_queries = config
+        .length_of_queries
+        .unwrap_or(DEFAULT_LENGTH_OF_SAMPLED_QUERIES);
+
+    let (texts, decompression_timer) = read_raw_texts(config)?;
+    let texts: Vec<_> = texts
+        .seqs
+        .iter()
+        .filter(|text| text.len() >= length_of_queries)
+        .collect();
+
+    if texts.is_empty() {
+        return Err(BenchmarkError::input(
+            &config.input_texts.get_filepath(),
+            format!("no text is long enough to sample queries of length {length_of_queries}"),
+        ));
+    }
+
+    // the start positions of the texts in the concatenation of all possible query start positions
+    let mut text_starts = Vec::with_capacity(texts.len());
+    let mut num_start_positions = 0;
+    for text in &texts {
+        text_starts.push(num_start_positions);
+        num_start_positions += text.len() - length_of_queries + 1;
+    }
+
+    let mut rng = StdRng::seed_from_u64(config.query_seed.unwrap_or(DEFAULT_QUERY_SAMPLING_SEED));
+
+    let mut queries = Records::default();
+
+    for _ in 0..num_queries {
+        let position = rng.gen_range(0..num_start_positions);
+        let text_index = text_starts.partition_point(|&start| start <= position) - 1;
+        let start = position - text_starts[text_index];
+
+        // the index refers to the texts that are long enough
+        queries.push(
+            format!("{text_index}:{start}").as_bytes(),
+            &texts[text_index][start..start + length_of_queries],
+        );
+    }
+
+    Ok((queries, decompression_timer))
+}
+
+// FASTA files start with a '>', FASTQ files with a '@'
+fn is_fasta_file(path: &Path) -> Result<bool, BenchmarkError> {
+    let mut first_byte = [0u8];
+    InputReader::open(path)?
+        .read_exact(&mut first_byte)
+        .map_err(|e| BenchmarkError::input(path, e))?;
+
+    Ok(first_byte[0] == b'>')
+}
+
+// the decompression time is always reported, because it would otherwise be hidden in the reading time
+fn log_reading_time(
+    name: &str,
+    start: Instant,
+    decompression_timer: &DecompressionTimer,
+    verbose: bool,
+) {
+    let total_secs = start.elapsed().as_secs_f64();
+
+    if decompression_timer.compression() == Compression::None {
+        if verbose {
+            info!("Reading time of the {name}: {total_secs:.2} seconds");
+        }
+        return;
+    }
+
+    let decompression_secs = decompression_timer.elapsed().as_secs_f64();
+
+    info!(
+        "Decompression ({}) time of the {name}: {decompression_secs:.2} seconds, parsing time: {:.2} seconds",
+        decompression_timer.compression(),
+        total_secs - decompression_secs
+    );
+}
+
+fn transform_seqs(
+    records: &mut Records,
+    name: &str,
+    alphabet: Alphabet,
+    policy: DegeneratePolicy,
+    verbose: bool,
+) -> DegenerateSymbolStats {
+    let num_affected_sequences = transform_records(records, alphabet, policy);
+
+    if verbose || num_affected_sequences > 0 {
+        info!(
+            "Number of {name} with degenerate symbols: {num_affected_sequences}, handled using {policy}"
+        );
+    }
+
+    let texts_len = records.seqs.symbols.len();
+    let average_record_length = texts_len as f64 / records.seqs.len() as f64;
+
+    if verbose {
+        info!(
+            "Total length of {name}: {} MB, average record length: {:.1}",
+            texts_len / 1_000_000,
+            average_record_length
+        );
+    }
+
+    DegenerateSymbolStats {
+        policy,
+        num_affected_sequences,
+    }
+}
+
+// Returns the number of sequences with degenerate symbols. The names are kept in sync with the sequences,
+// parts and variants of a sequence keep its name.
+fn transform_records(records: &mut Records, alphabet: Alphabet, policy: DegeneratePolicy) -> usize {
+    // DNA and protein sequences are case-insensitive
+    if let Alphabet::Dna | Alphabet::Protein = alphabet {
+        records.seqs.symbols.to_mut().make_ascii_uppercase();
+    }
+
+    let mut is_degenerate = [true; 256];
+    for &symbol in alphabet.concrete_symbols() {
+        is_degenerate[symbol as usize] = false;
+    }
+
+    let contains_degenerate = |seq: &[u8]| seq.iter().any(|&symbol| is_degenerate[symbol as usize]);
+    let num_affected_sequences = records
+        .seqs
+        .iter()
+        .filter(|seq| contains_degenerate(seq))
+        .count();
+
+    if num_affected_sequences == 0 {
+        return 0;
+    }
+
+    match policy {
+        DegeneratePolicy::Replace(replacement_symbol) => {
+            for symbol in records.seqs.symbols.to_mut().iter_mut() {
+                if is_degenerate[*symbol as usize] {
+                    *symbol = replacement_symbol;
+                }
+            }
+        }
+        DegeneratePolicy::Drop | DegeneratePolicy::Split => {
+            // the kept parts are moved to the front of the buffer, so no second buffer is needed
+            let split = matches!(policy, DegeneratePolicy::Split);
+            let old_offsets = std::mem::replace(&mut records.seqs.offsets, vec![0]);
+            let offsets = &mut records.seqs.offsets;
+            let symbols = records.seqs.symbols.to_mut();
+            let mut names = Sequences::new();
+            let mut parts = Vec::new();
+            let mut write_position = 0;
+
+            for (i, range) in old_offsets.windows(2).enumerate() {
+                let (start, end) = (range[0], range[1]);
+                let seq = &symbols[start..end];
+
+                if !split {
+                    if !contains_degenerate(seq) {
+                        parts.push(start..end);
+                    }
+                } else {
+                    let mut part_start = start;
+                    for (position, &symbol) in (start..).zip(seq) {
+                        if is_degenerate[symbol as usize] {
+                            if position > part_start {
+                                parts.push(part_start..position);
+                            }
+                            part_start = position + 1;
+                        }
+                    }
+                    if end > part_start {
+                        parts.push(part_start..end);
+                    }
+                }
+
+                for part in parts.drain(..) {
+                    let part_len = part.len();
+                    symbols.copy_within(part, write_position);
+                    write_position += part_len;
+                    offsets.push(write_position);
+                    names.push(records.names.get(i));
+                }
+            }
+
+            symbols.truncate(write_position);
+            records.names = names;
+        }
+        DegeneratePolicy::Expand(max_variants) => {
+            let mut expanded_records = Records::default();
+
+            for (name, seq) in records.names.iter().zip(records.seqs.iter()) {
+                if contains_degenerate(seq) {
+                    for variant in expand_degenerate_symbols(
+                        seq.to_vec(),
+                        alphabet,
+                        &is_degenerate,
+                        max_variants,
+                    ) {
+                        expanded_records.push(name, &variant);
+                    }
+                } else {
+                    expanded_records.push(name, seq);
+                }
+            }
+
+            *records = expanded_records;
+        }
+    }
+
+    num_affected_sequences
+}
+
+// returns an empty Vec, if there are more than max_variants variants
+fn expand_degenerate_symbols(
+    seq: Vec<u8>,
+    alphabet: Alphabet,
+    is_degenerate: &[bool; 256],
+    max_variants: usize,
+) -> Vec<Vec<u8>> {
+    let degenerate_positions: Vec<_> = (0..seq.len())
+        .filter(|&i| is_degenerate[seq[i] as usize])
+        .collect();
+
+    let num_variants = degenerate_positions.iter().try_fold(1usize, |acc, &i| {
+        acc.checked_mul(alphabet.variants_of_degenerate(seq[i]).len())
+    });
+
+    if num_variants.is_none_or(|n| n > max_variants) {
+        return Vec::new();
+    }
+
+    let mut variants = vec![seq];
+
+    for i in degenerate_positions {
+        variants = variants
+            .into_iter()
+            .flat_map(|variant| {
+                alphabet
+                    .variants_of_degenerate(variant[i])
+                    .iter()
+                    .map(move |&symbol| {
+                        let mut variant = variant.clone();
+                        variant[i] = symbol;
+                        variant
+                    })
+            })
+            .collect();
+    }
+
+    variants
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the names are the indices of the sequences
+    fn records(seqs: &[&[u8]]) -> Records {
+        let mut records = Records::default();
+        for (i, seq) in seqs.iter().enumerate() {
+            records.push(i.to_string().as_bytes(), seq);
+        }
+        records
+    }
+
+    fn names_and_seqs(records: &Records) -> Vec<(&[u8], &[u8])> {
+        records.names.iter().zip(records.seqs.iter()).collect()
+    }
+
+    // one query per line, the queries are the line numbers
+    fn sample_lines(num_lines: usize, sample_size: usize, seed: u64) -> Vec<usize> {
+        let path = std::env::temp_dir().join(format!(
+            "rust-fmindex-benchmark-reservoir-test-{}-{num_lines}-{sample_size}-{seed}.txt",
+            std::process::id()
+        ));
+        let content: String = (0..num_lines).map(|i| format!("{i}\n")).collect();
+        std::fs::write(&path, content).unwrap();
+
+        let mut reader = QueryRecordReader {
+            format: QueryFileFormat::Lines(
+                BufReader::new(InputReader::open(&path).unwrap()),
+                Vec::new(),
+            ),
+            path: path.clone(),
+            length_of_queries: None,
+        };
+        let records =
+            reservoir_sample(&mut reader, sample_size, &mut StdRng::seed_from_u64(seed)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        records
+            .seqs
+            .iter()
+            .map(|seq| std::str::from_utf8(seq).unwrap().parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn reservoir_sample_keeps_file_order() {
+        let sample = sample_lines(1_000, 50, 7);
+
+        assert_eq!(sample.len(), 50);
+        assert!(sample.windows(2).all(|window| window[0] < window[1]));
+        assert!(sample.iter().all(|&line| line < 1_000));
+        // the sample is not biased to the start of the file
+        assert!(sample.last().unwrap() >= &500);
+    }
+
+    #[test]
+    fn reservoir_sample_is_deterministic() {
+        assert_eq!(sample_lines(1_000, 20, 1), sample_lines(1_000, 20, 1));
+        assert_ne!(sample_lines(1_000, 20, 1), sample_lines(1_000, 20, 2));
+    }
+
+    #[test]
+    fn reservoir_sample_of_all_records() {
+        assert_eq!(sample_lines(30, 30, 3), (0..30).collect::<Vec<_>>());
+        assert_eq!(sample_lines(30, 100, 3), (0..30).collect::<Vec<_>>());
+        assert!(sample_lines(30, 0, 3).is_empty());
+    }
+
+    #[test]
+    fn sequences_indexing() {
+        let mut seqs = Sequences::new();
+        for seq in [&b"ACGT"[..], b"", b"GGA", b"T"] {
+            seqs.push(seq);
+        }
+
+        assert_eq!(seqs.len(), 4);
+        assert_eq!(seqs.get(0), b"ACGT");
+        assert_eq!(seqs.get(1), b"");
+        assert_eq!(seqs.get(3), b"T");
+        assert_eq!(seqs.symbols(), b"ACGTGGAT");
+        assert!(seqs.iter().eq([&b"ACGT"[..], b"", b"GGA", b"T"]));
+        assert!(seqs.select([3, 0, 3]).iter().eq([&b"T"[..], b"ACGT", b"T"]));
+
+        seqs.clear();
+        assert_eq!(seqs.len(), 0);
+        assert_eq!(seqs.iter().count(), 0);
+        seqs.push(b"CA");
+        assert_eq!(seqs.get(0), b"CA");
+        assert_eq!(seqs.into_symbols(), b"CA");
+    }
+
+    #[test]
+    fn reverse_complement_into_reuses_the_target() {
+        let mut queries = Queries::new();
+        for query in [&b"AACG"[..], b"TNG"] {
+            queries.push(query);
+        }
+
+        let mut reverse_queries = Queries::new();
+        reverse_queries.push(b"GGGGGGGG");
+        queries.reverse_complement_into(&mut reverse_queries);
+
+        assert!(reverse_queries.iter().eq([&b"CGTT"[..], b"CNA"]));
+    }
+
+    #[test]
+    fn transform_records_replace() {
+        let mut records = records(&[b"ACgt", b"acNT", b"RYA"]);
+
+        let num_affected =
+            transform_records(&mut records, Alphabet::Dna, DegeneratePolicy::Replace(b'N'));
+
+        assert_eq!(num_affected, 2);
+        assert_eq!(
+            names_and_seqs(&records),
+            [(&b"0"[..], &b"ACGT"[..]), (b"1", b"ACNT"), (b"2", b"NNA")]
+        );
+    }
+
+    #[test]
+    fn transform_records_drop() {
+        let mut records = records(&[b"ACNT", b"ACGT", b"N", b"gg"]);
+
+        let num_affected = transform_records(&mut records, Alphabet::Dna, DegeneratePolicy::Drop);
+
+        assert_eq!(num_affected, 2);
+        assert_eq!(
+            names_and_seqs(&records),
+            [(&b"1"[..], &b"ACGT"[..]), (b"3", b"GG")]
+        );
+    }
+
+    #[test]
+    fn transform_records_split() {
+        let mut records = records(&[b"NNACNGTN", b"ACGT", b"NN", b"ARCYYG"]);
+
+        let num_affected = transform_records(&mut records, Alphabet::Dna, DegeneratePolicy::Split);
+
+        assert_eq!(num_affected, 3);
+        assert_eq!(
+            names_and_seqs(&records),
+            [
+                (&b"0"[..], &b"AC"[..]),
+                (b"0", b"GT"),
+                (b"1", b"ACGT"),
+                (b"3", b"A"),
+                (b"3", b"C"),
+                (b"3", b"G"),
+            ]
+        );
+    }
+
+    #[test]
+    fn transform_records_expand() {
+        let mut records = records(&[b"ARC", b"ACGT", b"NN", b"BY"]);
+
+        let num_affected =
+            transform_records(&mut records, Alphabet::Dna, DegeneratePolicy::Expand(8));
+
+        // NN has 16 variants, so it is dropped
+        assert_eq!(num_affected, 3);
+        assert_eq!(
+            names_and_seqs(&records),
+            [
+                (&b"0"[..], &b"AAC"[..]),
+                (b"0", b"AGC"),
+                (b"1", b"ACGT"),
+                (b"3", b"CC"),
+                (b"3", b"CT"),
+                (b"3", b"GC"),
+                (b"3", b"GT"),
+                (b"3", b"TC"),
+                (b"3", b"TT"),
+            ]
+        );
+    }
+
+    #[test]
+    fn expand_protein_degenerate_symbols() {
+        let alphabet = Alphabet::Protein;
+        let mut is_degenerate = [true; 256];
+        for &symbol in alphabet.concrete_symbols() {
+            is_degenerate[symbol as usize] = false;
+        }
+
+        assert_eq!(
+            expand_degenerate_symbols(b"MBZ".to_vec(), alphabet, &is_degenerate, 4),
+            [b"MDE", b"MDQ", b"MNE", b"MNQ"]
+        );
+        assert_eq!(
+            expand_degenerate_symbols(b"MX".to_vec(), alphabet, &is_degenerate, 19),
+            Vec::<Vec<u8>>::new()
+        );
+        assert_eq!(
+            expand_degenerate_symbols(b"MX".to_vec(), alphabet, &is_degenerate, 20).len(),
+            20
+        );
+        // only the wildcard stands for all concrete symbols
+        assert_eq!(
+            expand_degenerate_symbols(b"MB*".to_vec(), alphabet, &is_degenerate, 20),
+            Vec::<Vec<u8>>::new()
+        );
+    }
+
+    #[test]
+    fn transform_records_keeps_bytes() {
+        let mut records = records(&[b"Hello, World!\tN"]);
+
+        let num_affected = transform_records(&mut records, Alphabet::Bytes, DegeneratePolicy::Drop);
+
+        assert_eq!(num_affected, 0);
+        assert_eq!(
+            names_and_seqs(&records),
+            [(&b"0"[..], &b"Hello, World!\tN"[..])]
+        );
+    }
+}
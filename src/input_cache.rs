@@ -0,0 +1,324 @@
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use memmap2::Mmap;
+use xxhash_rust::xxh3::{Xxh3, xxh3_64};
+
+use crate::error::BenchmarkError;
+use crate::input::Sequences;
+
+const MAGIC_BYTES: &[u8; 8] = b"FMBCACHE";
+const FORMAT_VERSION: u64 = 2;
+
+// The transformed texts or queries in a binary format, such that the input files don't have to be parsed again.
+// All integers are stored as little endian u64. The layout is:
+// header: magic bytes, format version, source file size, source file modification time, source file checksum,
+//         transformation length, transformation, number of sequences with degenerate symbols, number of records
+// records: lengths of the names, lengths of the sequences, concatenated names, concatenated sequences
+// The concatenated sequences are stored at the end, such that they can be used directly from the memory map.
+pub struct InputCache {
+    path: PathBuf,
+    source_path: PathBuf,
+    source_size: u64,
+    // in nanoseconds since the Unix epoch
+    source_modification_time: u64,
+    // describes how the sequences were derived from the source file, e.g. the degenerate symbol policy
+    transformation: String,
+}
+
+// the names are not needed by the benchmark itself, so they are not loaded
+pub struct CachedRecords {
+    pub seqs: Sequences,
+    pub num_affected_sequences: usize,
+}
+
+impl InputCache {
+    pub fn new(source_path: &Path, transformation: String) -> Result<Self, BenchmarkError> {
+        let metadata =
+            std::fs::metadata(source_path).map_err(|e| BenchmarkError::input(source_path, e))?;
+        let source_modification_time = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |duration| duration.as_nanos() as u64);
+
+        let source_file_name = source_path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+
+        let path = PathBuf::from(format!(
+            "cache/{source_file_name}-{:016x}.bin",
+            xxh3_64(transformation.as_bytes())
+        ));
+
+        Ok(Self {
+            path,
+            source_path: source_path.to_path_buf(),
+            source_size: metadata.len(),
+            source_modification_time,
+            transformation,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Returns None, if there is no cache file or it was created from a different source file or transformation.
+    // The source file is only hashed, if its size is the same, but its modification time differs, e.g. after a copy.
+    pub fn load(&self) -> Result<Option<CachedRecords>, BenchmarkError> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let file = File::open(&self.path).map_err(|e| BenchmarkError::input(&self.path, e))?;
+        // safety: the cache files are only written to temporary files that are renamed afterwards
+        let mmap = unsafe { Mmap::map(&file) }.map_err(|e| BenchmarkError::input(&self.path, e))?;
+
+        let mut cursor = Cursor {
+            bytes: &mmap,
+            offset: 0,
+        };
+
+        if cursor.take(MAGIC_BYTES.len()) != Some(&MAGIC_BYTES[..])
+            || cursor.take_u64() != Some(FORMAT_VERSION)
+            || cursor.take_u64() != Some(self.source_size)
+        {
+            return Ok(None);
+        }
+
+        let modification_time = cursor.take_u64();
+        let checksum = cursor.take_u64();
+        let transformation_is_same = cursor
+            .take_u64()
+            .and_then(|len| cursor.take(len as usize))
+            .is_some_and(|transformation| transformation == self.transformation.as_bytes());
+
+        if !transformation_is_same
+            || (modification_time != Some(self.source_modification_time)
+                && checksum != Some(file_checksum(&self.source_path)?))
+        {
+            return Ok(None);
+        }
+
+        let Some((num_affected_sequences, seq_lengths, symbols_range)) = read_records(&mut cursor)
+        else {
+            return Err(BenchmarkError::input(
+                &self.path,
+                "the cache file is truncated",
+            ));
+        };
+
+        Ok(Some(CachedRecords {
+            seqs: Sequences::from_mmap(
+                mmap,
+                symbols_range,
+                seq_lengths.into_iter().map(|len| len as usize),
+            ),
+            num_affected_sequences,
+        }))
+    }
+
+    pub fn store(
+        &self,
+        names: &Sequences,
+        seqs: &Sequences,
+        num_affected_sequences: usize,
+    ) -> Result<(), BenchmarkError> {
+        // concurrent benchmark runs should never see a partially written cache file
+        let temporary_path = self
+            .path
+            .with_extension(format!("{}.tmp", std::process::id()));
+
+        let source_checksum = file_checksum(&self.source_path)?;
+
+        self.write_to(
+            &temporary_path,
+            source_checksum,
+            names,
+            seqs,
+            num_affected_sequences,
+        )
+        .and_then(|_| std::fs::rename(&temporary_path, &self.path))
+        .map_err(|e| BenchmarkError::io(&self.path, e))
+    }
+
+    fn write_to(
+        &self,
+        path: &Path,
+        source_checksum: u64,
+        names: &Sequences,
+        seqs: &Sequences,
+        num_affected_sequences: usize,
+    ) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        writer.write_all(MAGIC_BYTES)?;
+        for value in [
+            FORMAT_VERSION,
+            self.source_size,
+            self.source_modification_time,
+            source_checksum,
+            self.transformation.len() as u64,
+        ] {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        writer.write_all(self.transformation.as_bytes())?;
+
+        for value in [num_affected_sequences as u64, seqs.len() as u64] {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+
+        for name in names.iter() {
+            writer.write_all(&(name.len() as u64).to_le_bytes())?;
+        }
+        for seq in seqs.iter() {
+            writer.write_all(&(seq.len() as u64).to_le_bytes())?;
+        }
+        writer.write_all(names.symbols())?;
+        writer.write_all(seqs.symbols())?;
+
+        writer.flush()
+    }
+}
+
+// returns the number of sequences with degenerate symbols, the lengths of the sequences and the range of their
+// concatenation in the cache file
+fn read_records(cursor: &mut Cursor) -> Option<(usize, Vec<u64>, Range<usize>)> {
+    let num_affected_sequences = cursor.take_u64()? as usize;
+    let num_records = cursor.take_u64()? as usize;
+
+    let name_lengths = cursor.take_u64s(num_records)?;
+    let seq_lengths = cursor.take_u64s(num_records)?;
+
+    cursor.take(name_lengths.iter().sum::<u64>() as usize)?;
+
+    let start = cursor.offset;
+    cursor.take(seq_lengths.iter().sum::<u64>() as usize)?;
+
+    Some((num_affected_sequences, seq_lengths, start..cursor.offset))
+}
+
+// of the raw bytes, so compressed files don't have to be decompressed
+fn file_checksum(path: &Path) -> Result<u64, BenchmarkError> {
+    let mut file = File::open(path).map_err(|e| BenchmarkError::input(path, e))?;
+    let mut hasher = Xxh3::new();
+    let mut buffer = vec![0; 1 << 20];
+
+    loop {
+        let num_bytes = file
+            .read(&mut buffer)
+            .map_err(|e| BenchmarkError::input(path, e))?;
+
+        if num_bytes == 0 {
+            break;
+        }
+
+        hasher.update(&buffer[..num_bytes]);
+    }
+
+    Ok(hasher.digest())
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.bytes.get(self.offset..self.offset.checked_add(len)?)?;
+        self.offset += len;
+        Some(bytes)
+    }
+
+    fn take_u64(&mut self) -> Option<u64> {
+        self.take(8)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn take_u64s(&mut self, len: usize) -> Option<Vec<u64>> {
+        (0..len).map(|_| self.take_u64()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+
+    fn sequences(seqs: &[&[u8]]) -> Sequences {
+        let mut sequences = Sequences::new();
+        for seq in seqs {
+            sequences.push(seq);
+        }
+        sequences
+    }
+
+    // the cache file is written next to the source file instead of into the cache folder
+    fn cache_for(source_path: &Path, transformation: &str) -> InputCache {
+        let mut cache = InputCache::new(source_path, transformation.to_string()).unwrap();
+        cache.path = source_path.with_extension(format!("{transformation}.bin"));
+        cache
+    }
+
+    fn set_modification_time(path: &Path, time: SystemTime) {
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
+    #[test]
+    fn store_and_load() {
+        let dir = std::env::temp_dir().join(format!(
+            "rust-fmindex-benchmark-input-cache-test-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source_path = dir.join("queries.fasta");
+        std::fs::write(&source_path, ">a\nACGT\n>b\n\n>c\nNNA\n").unwrap();
+
+        let names = sequences(&[b"a", b"b", b"c"]);
+        let seqs = sequences(&[b"ACGT", b"", b"AAA"]);
+
+        let cache = cache_for(&source_path, "replace");
+        assert!(cache.load().unwrap().is_none());
+        cache.store(&names, &seqs, 1).unwrap();
+
+        let cached_records = cache.load().unwrap().unwrap();
+        assert_eq!(cached_records.num_affected_sequences, 1);
+        assert_eq!(cached_records.seqs.len(), 3);
+        assert!(cached_records.seqs.iter().eq(seqs.iter()));
+
+        // another transformation of the same source file has its own cache
+        let other_cache = InputCache {
+            transformation: String::from("drop"),
+            ..cache_for(&source_path, "replace")
+        };
+        assert!(other_cache.load().unwrap().is_none());
+
+        // the source file is only hashed, if the modification time changed
+        set_modification_time(
+            &source_path,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000),
+        );
+        assert!(cache_for(&source_path, "replace").load().unwrap().is_some());
+
+        // same size, but other content
+        std::fs::write(&source_path, ">a\nACGT\n>b\n\n>c\nNNC\n").unwrap();
+        assert!(cache_for(&source_path, "replace").load().unwrap().is_none());
+
+        std::fs::write(&source_path, ">a\nACGT\n").unwrap();
+        assert!(cache_for(&source_path, "replace").load().unwrap().is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
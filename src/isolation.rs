@@ -0,0 +1,269 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{BenchmarkResult, Config};
+
+// Runs the benchmark of a library in a supervised child process, such that segmentation faults, panics,
+// running out of memory or hanging of a library doesn't abort the whole benchmark.
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub enum RunStatus {
+    #[default]
+    Success,
+    Panic,
+    Signal(i32),
+    OutOfMemory,
+    Timeout,
+    // the process exited with an unexpected exit code
+    ExitCode(i32),
+    // the benchmark stopped cleanly with an error, for example due to missing input files
+    Error(String),
+}
+
+impl Display for RunStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunStatus::Success => write!(f, "success"),
+            RunStatus::Panic => write!(f, "failed (panic)"),
+            RunStatus::Signal(signal) => write!(f, "failed (signal {signal})"),
+            RunStatus::OutOfMemory => write!(f, "failed (out of memory)"),
+            RunStatus::Timeout => write!(f, "failed (timeout)"),
+            RunStatus::ExitCode(code) => write!(f, "failed (exit code {code})"),
+            RunStatus::Error(message) => write!(f, "failed ({message})"),
+        }
+    }
+}
+
+const PANIC_EXIT_CODE: i32 = 101;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// this is what the Rust standard library prints before aborting when an allocation fails
+const ALLOCATION_FAILURE_MESSAGE: &[u8] = b"memory allocation of";
+
+pub fn run_supervised(config: &Config) -> BenchmarkResult {
+    // unique, such that concurrent supervisors that share the temporary directory don't collide
+    let worker_result_path = std::env::temp_dir().join(format!(
+        "rust-fmindex-benchmark-worker-{}-{}-{:016x}.json",
+        config.library,
+        std::process::id(),
+        rand::random::<u64>()
+    ));
+
+    let result = supervise_worker(config, &worker_result_path).unwrap_or_else(|status| {
+        warn!("Benchmark for {} {status}", config.library);
+
+        let mut result = BenchmarkResult::new_empty(config.clone());
+        result.status = status;
+        result
+    });
+
+    if let Err(e) = std::fs::remove_file(&worker_result_path)
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        warn!(
+            "Failed to remove the temporary result file {}: {e}",
+            worker_result_path.display()
+        );
+    }
+
+    result
+}
+
+// the worker records errors that it handled itself in its result, all other failures are returned as the status
+fn supervise_worker(
+    config: &Config,
+    worker_result_path: &Path,
+) -> Result<BenchmarkResult, RunStatus> {
+    let current_exe = std::env::current_exe()
+        .map_err(|e| RunStatus::Error(format!("failed to find the benchmark executable: {e}")))?;
+
+    let mut command = Command::new(current_exe);
+    command
+        .args(std::env::args_os().skip(1))
+        .arg("--worker-result-path")
+        .arg(worker_result_path)
+        .stderr(Stdio::piped());
+
+    if let Some(memory_limit_mb) = config.memory_limit_mb {
+        set_memory_limit(&mut command, memory_limit_mb);
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| RunStatus::Error(format!("failed to start the benchmark process: {e}")))?;
+
+    let stderr_thread = child
+        .stderr
+        .take()
+        .map(|stderr| std::thread::spawn(move || forward_stderr_and_detect_oom(stderr)));
+
+    let timeout = config.timeout_secs.map(Duration::from_secs);
+    let exit_status = wait_with_timeout(&mut child, timeout)
+        .map_err(|e| RunStatus::Error(format!("failed to wait for the benchmark process: {e}")))?;
+    let allocation_failed =
+        stderr_thread.is_some_and(|stderr_thread| stderr_thread.join().unwrap_or(false));
+
+    // the supervisor only kills the child on timeout, so the exit status is only classified if it ended by itself
+    match exit_status.map(|exit_status| classify_exit_status(exit_status, allocation_failed)) {
+        Some(RunStatus::Success) => read_worker_result(worker_result_path),
+        Some(status) => Err(status),
+        None => Err(RunStatus::Timeout),
+    }
+}
+
+pub fn write_worker_result(result: &BenchmarkResult, path: &Path) {
+    let file = File::create(path).unwrap();
+    serde_json::to_writer(file, result).unwrap();
+}
+
+// a worker that exited successfully without a complete result is recorded as failed
+fn read_worker_result(path: &Path) -> Result<BenchmarkResult, RunStatus> {
+    let file = File::open(path).map_err(|e| {
+        RunStatus::Error(format!(
+            "missing result of the benchmark process at {}: {e}",
+            path.display()
+        ))
+    })?;
+
+    serde_json::from_reader(BufReader::new(file)).map_err(|e| {
+        RunStatus::Error(format!(
+            "invalid result of the benchmark process at {}: {e}",
+            path.display()
+        ))
+    })
+}
+
+// returns None if the timeout was reached
+fn wait_with_timeout(
+    child: &mut Child,
+    timeout: Option<Duration>,
+) -> std::io::Result<Option<ExitStatus>> {
+    let start = Instant::now();
+
+    loop {
+        if let Some(exit_status) = child.try_wait()? {
+            return Ok(Some(exit_status));
+        }
+
+        if timeout.is_some_and(|timeout| start.elapsed() >= timeout) {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn forward_stderr_and_detect_oom(stderr: impl Read) -> bool {
+    let mut reader = BufReader::new(stderr);
+    let mut line = Vec::new();
+    let mut allocation_failed = false;
+
+    // a failed read ends the forwarding, the exit status of the child is still classified
+    while reader
+        .read_until(b'\n', &mut line)
+        .is_ok_and(|num_bytes| num_bytes > 0)
+    {
+        allocation_failed |= line
+            .windows(ALLOCATION_FAILURE_MESSAGE.len())
+            .any(|window| window == ALLOCATION_FAILURE_MESSAGE);
+
+        // the output of the child is only forwarded for information
+        let _ = std::io::stderr().write_all(&line);
+        line.clear();
+    }
+
+    allocation_failed
+}
+
+// A failed allocation, for example due to the address space limit, is only detected by the message of the standard
+// library. Other kills, like by the OOM killer of the system, can't be told apart from other causes and are reported
+// as a signal.
+#[cfg(unix)]
+fn classify_exit_status(exit_status: ExitStatus, allocation_failed: bool) -> RunStatus {
+    use std::os::unix::process::ExitStatusExt;
+
+    match (exit_status.code(), exit_status.signal()) {
+        (Some(0), _) => RunStatus::Success,
+        _ if allocation_failed => RunStatus::OutOfMemory,
+        (Some(PANIC_EXIT_CODE), _) => RunStatus::Panic,
+        (Some(code), _) => RunStatus::ExitCode(code),
+        (None, Some(signal)) => RunStatus::Signal(signal),
+        (None, None) => unreachable!(),
+    }
+}
+
+#[cfg(windows)]
+fn classify_exit_status(exit_status: ExitStatus, allocation_failed: bool) -> RunStatus {
+    match exit_status.code().unwrap() {
+        0 => RunStatus::Success,
+        _ if allocation_failed => RunStatus::OutOfMemory,
+        PANIC_EXIT_CODE => RunStatus::Panic,
+        code => RunStatus::ExitCode(code),
+    }
+}
+
+#[cfg(unix)]
+fn set_memory_limit(command: &mut Command, memory_limit_mb: u64) {
+    use std::os::unix::process::CommandExt;
+
+    let limit_bytes = (memory_limit_mb * 1_000_000) as libc::rlim_t;
+
+    // safety: setrlimit is async-signal-safe and the closure doesn't allocate
+    unsafe {
+        command.pre_exec(move || {
+            let limit = libc::rlimit {
+                rlim_cur: limit_bytes,
+                rlim_max: limit_bytes,
+            };
+
+            if libc::setrlimit(libc::RLIMIT_AS, &limit) == 0 {
+                Ok(())
+            } else {
+                Err(std::io::Error::last_os_error())
+            }
+        });
+    }
+
+    info!("Memory limit of the benchmark process: {memory_limit_mb} MB");
+}
+
+#[cfg(windows)]
+fn set_memory_limit(_command: &mut Command, _memory_limit_mb: u64) {
+    warn!("The memory limit is currently not supported on windows and will be ignored");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_or_truncated_worker_result() {
+        let path = std::env::temp_dir().join(format!(
+            "rust-fmindex-benchmark-test-worker-result-{}.json",
+            std::process::id()
+        ));
+
+        assert!(matches!(
+            read_worker_result(&path),
+            Err(RunStatus::Error(message)) if message.starts_with("missing")
+        ));
+
+        std::fs::write(&path, b"{\"config\": {").unwrap();
+        let result = read_worker_result(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            result,
+            Err(RunStatus::Error(message)) if message.starts_with("invalid")
+        ));
+    }
+}
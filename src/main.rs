@@ -1,24 +1,85 @@
+// with only some of the libraries enabled, parts of the shared benchmark code are unused
+#![cfg_attr(
+    not(all(
+        feature = "awry",
+        feature = "fm-index",
+        feature = "genedex",
+        feature = "rust-bio",
+        feature = "sview-fmindex"
+    )),
+    allow(dead_code)
+)]
+
+#[cfg(feature = "awry")]
 mod awry_bench;
+#[cfg(feature = "rust-bio")]
 mod bio_bench;
 mod common_interface;
+mod compression;
+mod error;
+#[cfg(feature = "fm-index")]
 mod fmindex_bench;
+#[cfg(feature = "genedex")]
 mod genedex_bench;
+mod hit_stats;
+mod input;
+mod input_cache;
+mod isolation;
+mod registry;
+mod report;
+mod soft_mask;
+#[cfg(feature = "sview-fmindex")]
 mod sview_fmindex_bench;
 
-use crate::common_interface::{BenchmarkFmIndex, SearchMetrics};
-use clap::{Parser, ValueEnum};
-use log::info;
+use crate::common_interface::SearchMetrics;
+use crate::compression::{InputReader, find_input_file};
+use crate::error::BenchmarkError;
+use crate::input::DegenerateSymbolStats;
+use crate::isolation::RunStatus;
+use crate::report::ReportConfig;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use log::{error, info};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, convert::identity, fmt::Display, fs::File, path::PathBuf};
+use std::{
+    collections::HashMap, convert::identity, fmt::Display, fs::File, path::PathBuf, str::FromStr,
+};
 use strum::Display;
 
-#[derive(Serialize, Deserialize, Debug, Parser, Clone, PartialEq, Eq)]
+#[derive(Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the benchmark for a single library configuration and store the results
+    Bench(Box<Config>),
+    /// Generate the plots and a Markdown summary from the stored results
+    Report(ReportConfig),
+}
+
+#[derive(Serialize, Deserialize, Debug, Args, Clone, PartialEq, Eq)]
 struct Config {
-    library: Library,
+    #[arg(value_parser = registry::library_value_parser())]
+    library: String,
 
     #[arg(short, long)]
     input_texts: InputTexts,
 
+    // how plain text inputs are split into multiple texts
+    #[arg(long, default_value = "lines")]
+    #[serde(default)]
+    text_split: TextSplit,
+
+    // how symbols outside of the alphabet are handled, by default they are replaced by the wildcard in the texts
+    // and by A in the queries. Possible values: replace:<symbol>, drop, split, expand:<max variants>
+    #[arg(long)]
+    texts_degenerate_policy: Option<DegeneratePolicy>,
+
+    #[arg(long)]
+    queries_degenerate_policy: Option<DegeneratePolicy>,
+
     #[arg(short, long, default_value_t = 4)]
     suffix_array_sampling_rate: usize,
 
@@ -31,8 +92,16 @@ struct Config {
     #[arg(short, long)]
     extra_build_arg: Option<ExtraBuildArg>,
 
-    #[arg(short, long, default_value = "data/reads.fastq")]
-    queries_path: PathBuf,
+    // lowercase symbols of DNA texts are treated as repeats, the search is also run separately for repeat-derived
+    // and unique queries
+    #[arg(long)]
+    #[serde(default)]
+    soft_mask: bool,
+
+    // FASTA or FASTQ, by default the reads for DNA and the peptides for protein inputs.
+    // For the byte alphabet, one query per line. By default, the queries are sampled from the texts.
+    #[arg(short, long)]
+    queries_path: Option<PathBuf>,
 
     #[arg(short = 'm', long)]
     num_queries_records: Option<usize>,
@@ -40,12 +109,63 @@ struct Config {
     #[arg(short, long)]
     length_of_queries: Option<usize>,
 
+    // run the search for each of these query lengths with the same index, instead of only for length_of_queries
+    #[arg(long, value_delimiter = ',')]
+    #[serde(default)]
+    query_length_sweep: Vec<usize>,
+
+    // sample the queries uniformly from the whole file instead of taking the first records, also used for the
+    // shuffled query order
+    #[arg(long)]
+    #[serde(default)]
+    query_seed: Option<u64>,
+
+    // the order in which the queries are searched, which influences the cache behavior of the search
+    #[arg(long, default_value = "file")]
+    #[serde(default)]
+    query_order: QueryOrder,
+
     #[arg(short = 'o', long, default_value = "locate")]
     search_mode: SearchMode,
 
+    // only used by the locate-first-k search mode
+    #[arg(long, default_value_t = 10)]
+    #[serde(default)]
+    first_k: usize,
+
+    // only used by the approximate search modes
+    #[arg(short = 'k', long, default_value_t = 1)]
+    #[serde(default)]
+    max_errors: usize,
+
+    // only used by the edits search mode
+    #[arg(long, default_value = "canonical")]
+    #[serde(default)]
+    edit_pruning: EditPruning,
+
+    // only used by the smems search mode, the default is the one of BWA-MEM
+    #[arg(long, default_value_t = 19)]
+    #[serde(default)]
+    min_seed_length: usize,
+
+    // analyze the number of hits per query and the count and locate time per hit count in an additional pass
+    #[arg(long)]
+    #[serde(default)]
+    hit_stats: bool,
+
+    // also search the reverse complement of every query, as an alternative to indexing the doubled hg38 text
+    #[arg(long)]
+    #[serde(default)]
+    both_strands: bool,
+
     #[arg(short, long, default_value_t = 5)]
     repeat_search: usize,
 
+    // read the queries from disk during the search in chunks of this many records, instead of all at once
+    #[arg(long, value_name = "CHUNK_SIZE")]
+    #[serde(default)]
+    stream_queries: Option<usize>,
+
     #[arg(long)]
     skip_build: bool,
 
@@ -54,6 +174,29 @@ struct Config {
 
     #[arg(short, long)]
     verbose: bool,
+
+    // stores the transformed texts and queries in a binary format in the cache folder and uses them in later runs,
+    // as long as the input files and the transformation are the same
+    #[arg(long)]
+    #[serde(default)]
+    input_cache: bool,
+
+    // the benchmark is killed after this time and recorded as failed
+    #[arg(long)]
+    timeout_secs: Option<u64>,
+
+    // limits the address space of the benchmark process, only supported on unix
+    #[arg(long)]
+    memory_limit_mb: Option<u64>,
+
+    // run in this process instead of a supervised child process, e.g. for profiling
+    #[arg(long)]
+    no_isolation: bool,
+
+    // set internally when this process is the supervised child process
+    #[arg(long, hide = true)]
+    #[serde(skip)]
+    worker_result_path: Option<PathBuf>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, ValueEnum, PartialEq, Eq, Display)]
@@ -65,35 +208,136 @@ enum ExtraBuildArg {
 impl Config {
     fn index_filepath(&self) -> PathBuf {
         PathBuf::from(format!(
-            "indices/{}_sampling_rate_{}_lookup_depth_{}_text_records_{}.index",
+            "indices/{}_sampling_rate_{}_lookup_depth_{}_text_records_{}{}.index",
             self.library,
             self.suffix_array_sampling_rate,
-            self.depth_of_lookup_table,
+            self.lookup_table_depth(),
             self.input_texts,
+            self.texts_transformation_suffix(),
         ))
     }
 
+    // empty for the default transformation of the input texts, to keep the names of older indices and results valid
+    fn texts_transformation_suffix(&self) -> String {
+        let mut suffix = String::new();
+
+        // only plain texts are split
+        if self.alphabet() == Alphabet::Bytes && self.text_split != TextSplit::default() {
+            suffix += &format!("_split_{}", self.text_split);
+        }
+
+        let texts_degenerate_policy = self.texts_degenerate_policy();
+        if texts_degenerate_policy != DegeneratePolicy::Replace(self.alphabet().wildcard()) {
+            // without the colon, which is not allowed in file names on Windows
+            suffix += &format!("_{}", texts_degenerate_policy.to_string().replace(':', "_"));
+        }
+
+        suffix
+    }
+
+    fn queries_path(&self) -> Option<PathBuf> {
+        self.queries_path.clone().or_else(|| match self.alphabet() {
+            Alphabet::Dna => Some(find_input_file(PathBuf::from("data/reads.fastq"))),
+            Alphabet::Protein => Some(find_input_file(PathBuf::from("data/peptides.fasta"))),
+            Alphabet::Bytes => None,
+        })
+    }
+
+    fn alphabet(&self) -> Alphabet {
+        self.input_texts.alphabet()
+    }
+
+    // The lookup tables have an entry for every string of their depth over the alphabet, so the depth is reduced
+    // for the larger alphabets to keep the tables about as large as the one of the default depth for DNA.
+    fn lookup_table_depth(&self) -> usize {
+        let num_symbols = self.alphabet().concrete_symbols().len();
+
+        (0..self.depth_of_lookup_table)
+            .take_while(|&depth| {
+                num_symbols
+                    .checked_pow(depth as u32 + 1)
+                    .is_some_and(|num_entries| num_entries <= MAX_LOOKUP_TABLE_ENTRIES)
+            })
+            .count()
+    }
+
+    fn texts_degenerate_policy(&self) -> DegeneratePolicy {
+        self.texts_degenerate_policy
+            .unwrap_or(DegeneratePolicy::Replace(self.alphabet().wildcard()))
+    }
+
+    fn queries_degenerate_policy(&self) -> DegeneratePolicy {
+        self.queries_degenerate_policy
+            .unwrap_or(DEFAULT_QUERIES_DEGENERATE_POLICY)
+    }
+
     fn search_config(&self) -> SearchConfig {
         SearchConfig {
             search_mode: self.search_mode,
             num_queries_records: self.num_queries_records,
             length_of_queries: self.length_of_queries,
+            queries_degenerate_policy: self.queries_degenerate_policy(),
+            query_seed: self.query_seed,
+            query_order: self.query_order,
+            stream_queries: self.stream_queries,
+            both_strands: self.both_strands,
+            first_k: (self.search_mode == SearchMode::LocateFirstK).then_some(self.first_k),
+            max_errors: self.search_mode.is_approximate().then_some(self.max_errors),
+            edit_pruning: (self.search_mode == SearchMode::Edits).then_some(self.edit_pruning),
+            min_seed_length: (self.search_mode == SearchMode::Smems)
+                .then_some(self.min_seed_length),
         }
     }
 
+    // one config per query length of the sweep
+    fn configs_for_query_lengths(&self) -> Vec<Config> {
+        if self.query_length_sweep.is_empty() {
+            return vec![self.clone()];
+        }
+
+        self.query_length_sweep
+            .iter()
+            .map(|&length_of_queries| Config {
+                length_of_queries: Some(length_of_queries),
+                ..self.clone()
+            })
+            .collect()
+    }
+
     fn has_same_index_config_as(&self, other: &Config) -> bool {
         self.build_thread_count == other.build_thread_count
-            && self.depth_of_lookup_table == other.depth_of_lookup_table
+            && self.lookup_table_depth() == other.lookup_table_depth()
             && self.library == other.library
             && self.suffix_array_sampling_rate == other.suffix_array_sampling_rate
+            && self.texts_transformation_suffix() == other.texts_transformation_suffix()
     }
 }
 
+// the number of entries of the lookup table of depth 13 for DNA
+const MAX_LOOKUP_TABLE_ENTRIES: usize = 4usize.pow(13);
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
 struct SearchConfig {
     search_mode: SearchMode,
     num_queries_records: Option<usize>,
     length_of_queries: Option<usize>,
+    queries_degenerate_policy: DegeneratePolicy,
+    #[serde(default)]
+    query_seed: Option<u64>,
+    #[serde(default)]
+    query_order: QueryOrder,
+    #[serde(default)]
+    stream_queries: Option<usize>,
+    #[serde(default)]
+    both_strands: bool,
+    #[serde(default)]
+    first_k: Option<usize>,
+    #[serde(default)]
+    max_errors: Option<usize>,
+    #[serde(default)]
+    edit_pruning: Option<EditPruning>,
+    #[serde(default)]
+    min_seed_length: Option<usize>,
 }
 
 impl Display for SearchConfig {
@@ -106,7 +350,92 @@ impl Display for SearchConfig {
                 .map_or_else(|| String::from("all"), |n| n.to_string()),
             self.length_of_queries
                 .map_or_else(|| String::from("full"), |l| l.to_string())
-        )
+        )?;
+
+        // to keep the keys of older results valid
+        if self.queries_degenerate_policy != DEFAULT_QUERIES_DEGENERATE_POLICY {
+            write!(f, "-{}", self.queries_degenerate_policy)?;
+        }
+
+        if let Some(first_k) = self.first_k {
+            write!(f, "-first-{first_k}")?;
+        }
+
+        if let Some(max_errors) = self.max_errors {
+            write!(f, "-k-{max_errors}")?;
+        }
+
+        if let Some(edit_pruning) = self.edit_pruning
+            && edit_pruning != EditPruning::default()
+        {
+            write!(f, "-pruning-{edit_pruning}")?;
+        }
+
+        if let Some(min_seed_length) = self.min_seed_length {
+            write!(f, "-min-{min_seed_length}")?;
+        }
+
+        if let Some(seed) = self.query_seed {
+            write!(f, "-seed-{seed}")?;
+        }
+
+        if self.query_order != QueryOrder::default() {
+            write!(f, "-order-{}", self.query_order)?;
+        }
+
+        if let Some(chunk_size) = self.stream_queries {
+            write!(f, "-stream-{chunk_size}")?;
+        }
+
+        if self.both_strands {
+            write!(f, "-both-strands")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+enum DegeneratePolicy {
+    Replace(u8),
+    Drop,
+    // into the parts between the degenerate symbols
+    Split,
+    // into all concrete variants, sequences with more variants are dropped. Only for queries.
+    Expand(usize),
+}
+
+const DEFAULT_QUERIES_DEGENERATE_POLICY: DegeneratePolicy = DegeneratePolicy::Replace(b'A');
+
+impl FromStr for DegeneratePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "drop" => Ok(DegeneratePolicy::Drop),
+            None if s == "split" => Ok(DegeneratePolicy::Split),
+            Some(("replace", symbol)) if symbol.len() == 1 => {
+                Ok(DegeneratePolicy::Replace(symbol.as_bytes()[0]))
+            }
+            Some(("expand", max_variants)) => max_variants
+                .parse()
+                .map(DegeneratePolicy::Expand)
+                .map_err(|e| format!("invalid maximum number of variants: {e}")),
+            _ => Err(String::from(
+                "expected replace:<symbol>, drop, split or expand:<max variants>",
+            )),
+        }
+    }
+}
+
+impl Display for DegeneratePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DegeneratePolicy::Replace(symbol) => write!(f, "replace:{}", *symbol as char),
+            DegeneratePolicy::Drop => write!(f, "drop"),
+            DegeneratePolicy::Split => write!(f, "split"),
+            DegeneratePolicy::Expand(max_variants) => write!(f, "expand:{max_variants}"),
+        }
     }
 }
 
@@ -116,43 +445,199 @@ enum InputTexts {
     I32,
     Hg38,
     DoubleHg38,
+    Proteome,
+    // Pizza&Chili-style plain text corpora
+    English,
+    Sources,
+    Xml,
+    Logs,
 }
 
 impl InputTexts {
+    fn is_small(&self) -> bool {
+        matches!(self, InputTexts::Chromosome | InputTexts::I32)
+    }
+
     fn get_filepath(&self) -> PathBuf {
-        match self {
+        // the files can also be compressed
+        let path = match self {
             InputTexts::Chromosome => PathBuf::from("data/chromosome.fna"),
             InputTexts::I32 => PathBuf::from("data/i32.fna"),
             InputTexts::Hg38 => PathBuf::from("data/hg38.fna"),
             InputTexts::DoubleHg38 => PathBuf::from("data/hg38_double.fna"),
+            InputTexts::Proteome => PathBuf::from("data/proteome.fasta"),
+            InputTexts::English => PathBuf::from("data/english.txt"),
+            InputTexts::Sources => PathBuf::from("data/sources.txt"),
+            InputTexts::Xml => PathBuf::from("data/xml.txt"),
+            InputTexts::Logs => PathBuf::from("data/logs.txt"),
+        };
+
+        find_input_file(path)
+    }
+
+    fn alphabet(&self) -> Alphabet {
+        match self {
+            InputTexts::Chromosome
+            | InputTexts::I32
+            | InputTexts::Hg38
+            | InputTexts::DoubleHg38 => Alphabet::Dna,
+            InputTexts::Proteome => Alphabet::Protein,
+            InputTexts::English | InputTexts::Sources | InputTexts::Xml | InputTexts::Logs => {
+                Alphabet::Bytes
+            }
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, ValueEnum, PartialEq, Eq, Hash, Display)]
-enum Library {
-    GenedexFlat64,
-    GenedexCond64,
-    GenedexCond512,
-    BioSmall,
-    BioLarge,
-    Awry,
-    FmIndexSingle,
-    FmIndexMulti,
-    SviewFmIndexVec32,
-    SviewFmIndexVec128,
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, ValueEnum, PartialEq, Eq, Hash, Display, Default,
+)]
+enum Alphabet {
+    #[default]
+    Dna,
+    Protein,
+    // all bytes except for 0 and 255, which never occur in UTF-8 texts. This way, the libraries can use the
+    // zero byte as separator and genedex can handle the alphabet size.
+    Bytes,
+}
+
+const BYTE_SYMBOLS: [u8; 254] = {
+    let mut symbols = [0; 254];
+    let mut i = 0;
+    while i < 254 {
+        symbols[i] = i as u8 + 1;
+        i += 1;
+    }
+    symbols
+};
+
+impl Alphabet {
+    // the symbols that are indexed, the last one is the wildcard that degenerate symbols of the texts are replaced with
+    fn symbols(&self) -> &'static [u8] {
+        match self {
+            Alphabet::Dna => b"ACGTN",
+            Alphabet::Protein => b"ACDEFGHIKLMNPQRSTVWYX",
+            Alphabet::Bytes => &BYTE_SYMBOLS,
+        }
+    }
+
+    fn wildcard(&self) -> u8 {
+        *self.symbols().last().unwrap()
+    }
+
+    // the symbols that are not degenerate
+    fn concrete_symbols(&self) -> &'static [u8] {
+        match self {
+            Alphabet::Dna | Alphabet::Protein => &self.symbols()[..self.symbols().len() - 1],
+            Alphabet::Bytes => self.symbols(),
+        }
+    }
+
+    // the concrete symbols that a degenerate symbol can stand for
+    fn variants_of_degenerate(&self, symbol: u8) -> &'static [u8] {
+        match (self, symbol) {
+            (Alphabet::Dna, b'R') => b"AG",
+            (Alphabet::Dna, b'Y') => b"CT",
+            (Alphabet::Dna, b'K') => b"GT",
+            (Alphabet::Dna, b'M') => b"AC",
+            (Alphabet::Dna, b'S') => b"CG",
+            (Alphabet::Dna, b'W') => b"AT",
+            (Alphabet::Dna, b'B') => b"CGT",
+            (Alphabet::Dna, b'D') => b"AGT",
+            (Alphabet::Dna, b'H') => b"ACT",
+            (Alphabet::Dna, b'V') => b"ACG",
+            (Alphabet::Protein, b'B') => b"DN",
+            (Alphabet::Protein, b'Z') => b"EQ",
+            (Alphabet::Protein, b'J') => b"IL",
+            _ if symbol == self.wildcard() => self.concrete_symbols(),
+            // e.g. gaps or stop codons, the sequences that contain them are dropped by the expansion
+            _ => &[],
+        }
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, ValueEnum, PartialEq, Eq, Display, Default,
+)]
+enum TextSplit {
+    // the whole file is a single text
+    Whole,
+    #[default]
+    Lines,
+    // separated by empty lines
+    Documents,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, ValueEnum, PartialEq, Eq, Hash, Display)]
 enum SearchMode {
     Count,
     Locate,
+    // only whether the query occurs at all
+    Exists,
+    // locate at most first_k occurrences per query, like mappers that cap multi-mapping reads
+    LocateFirstK,
+    // locate with up to max_errors substitutions, via the backward search cursor
+    Mismatches,
+    // locate with up to max_errors substitutions, insertions and deletions, via the backward search cursor
+    Edits,
+    // count the occurrences of the super-maximal exact matches of at least min_seed_length, like read mappers do
+    Smems,
+    // the longest suffix of every query that occurs in the texts, like in matching statistics
+    LongestSuffix,
+    // count via the backward search cursor, reusing the intervals of suffixes shared by the sorted queries
+    TrieCount,
+}
+
+impl SearchMode {
+    fn is_approximate(self) -> bool {
+        matches!(self, SearchMode::Mismatches | SearchMode::Edits)
+    }
+
+    fn uses_cursor(self) -> bool {
+        self.is_approximate() || matches!(self, SearchMode::Smems | SearchMode::TrieCount)
+    }
+
+    fn locates(self) -> bool {
+        matches!(self, SearchMode::Locate | SearchMode::LocateFirstK)
+    }
+}
+
+// applied after the queries are read or sampled
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, ValueEnum, PartialEq, Eq, Hash, Display, Default,
+)]
+enum QueryOrder {
+    #[default]
+    File,
+    // deterministic, with the query seed or the default seed
+    Shuffled,
+    Sorted,
+    // sorted by the reversed sequences, such that the backward searches of consecutive queries start alike
+    ReverseSorted,
+    // only the first occurrence of every distinct query, in file order
+    Deduplicated,
+}
+
+// Different edit operations can lead to the same occurrence. The canonical pruning skips some of these redundant
+// branches: no insertion directly after a deletion or vice versa (a substitution is cheaper), and no indels
+// at the ends of the query (the occurrence without the indel is found as well).
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, ValueEnum, PartialEq, Eq, Hash, Display, Default,
+)]
+enum EditPruning {
+    None,
+    #[default]
+    Canonical,
 }
 
 #[derive(Serialize, Deserialize)]
 struct BenchmarkResult {
     config: Config,
 
+    // the outcome of the latest run
+    #[serde(default)]
+    status: RunStatus,
+
     // only set when the build was not skipped
     construction_time_secs: Option<f64>,
     construction_peak_memory_usage_mb: Option<f64>,
@@ -160,6 +645,13 @@ struct BenchmarkResult {
     // only set when loading from disk to make sure that remaining from texts and build allocations don't count
     only_index_in_memory_size_mb: Option<f64>,
 
+    // only set when the texts were read by the benchmark harness, used for the construction memory factor
+    texts_size_mb: Option<f64>,
+
+    // only set when the texts were read by the benchmark harness
+    #[serde(default)]
+    texts_degenerate_symbols: Option<DegenerateSymbolStats>,
+
     search_metrics: HashMap<String, SearchMetrics>,
 
     // only set when file IO is available and was not skipped
@@ -171,9 +663,12 @@ impl BenchmarkResult {
     fn new_empty(config: Config) -> Self {
         Self {
             config,
+            status: RunStatus::Success,
             construction_time_secs: None,
             construction_peak_memory_usage_mb: None,
             only_index_in_memory_size_mb: None,
+            texts_size_mb: None,
+            texts_degenerate_symbols: None,
             search_metrics: HashMap::new(),
             write_to_file_time_secs: None,
             read_from_file_time_secs: None,
@@ -183,6 +678,8 @@ impl BenchmarkResult {
     fn update(&mut self, other: Self) {
         assert!(self.config.has_same_index_config_as(&other.config));
 
+        self.status = other.status;
+
         self.construction_time_secs = other.construction_time_secs.or(self.construction_time_secs);
         self.construction_peak_memory_usage_mb = other
             .construction_peak_memory_usage_mb
@@ -190,6 +687,10 @@ impl BenchmarkResult {
         self.only_index_in_memory_size_mb = other
             .only_index_in_memory_size_mb
             .or(self.only_index_in_memory_size_mb);
+        self.texts_size_mb = other.texts_size_mb.or(self.texts_size_mb);
+        self.texts_degenerate_symbols = other
+            .texts_degenerate_symbols
+            .or(self.texts_degenerate_symbols);
         self.write_to_file_time_secs = other
             .write_to_file_time_secs
             .or(self.write_to_file_time_secs);
@@ -205,64 +706,77 @@ impl BenchmarkResult {
 
 // input genome should be placed at data/hg38
 fn main() {
-    let config = Config::parse();
-
-    for dir_name in ["data", "indices", "logs", "results"] {
+    for dir_name in ["cache", "data", "indices", "logs", "results"] {
         if !std::fs::exists(dir_name).unwrap() {
             std::fs::create_dir(dir_name).unwrap();
         }
     }
 
-    setup_logger(config.library).unwrap();
+    match Cli::parse().command {
+        Command::Bench(config) => run_benchmark(*config),
+        Command::Report(report_config) => {
+            setup_logger("report").unwrap();
+            if let Err(e) = report::generate_report(&report_config) {
+                error!("Report failed: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+fn run_benchmark(config: Config) {
+    setup_logger(&config.library).unwrap();
 
+    if let Some(worker_result_path) = &config.worker_result_path {
+        let result = run_benchmark_in_this_process(&config);
+        isolation::write_worker_result(&result, worker_result_path);
+        return;
+    }
+
+    let result = if config.no_isolation {
+        run_benchmark_in_this_process(&config)
+    } else {
+        isolation::run_supervised(&config)
+    };
+
+    update_stored_results(result, config);
+}
+
+fn run_benchmark_in_this_process(config: &Config) -> BenchmarkResult {
     rayon::ThreadPoolBuilder::new()
         .num_threads(config.build_thread_count as usize)
         .build_global()
         .unwrap();
 
-    setup_input_data();
+    let result = setup_input_data(config).and_then(|_| {
+        info!(
+            "------------------------------ starting benchmark for {} ------------------------------",
+            config.library,
+        );
 
-    info!(
-        "------------------------------ starting benchmark for {} ------------------------------",
-        config.library,
-    );
+        if config.verbose {
+            info!("Configuration: {:#?}", config);
+        }
 
-    if config.verbose {
-        info!("Configuration: {:#?}", config);
-    }
+        let (_, variant) = registry::find(&config.library).unwrap();
+        (variant.run_benchmark)(config)
+    });
 
-    let result = match config.input_texts {
-        InputTexts::Chromosome => run_benchmark_for_index_type::<i32, u32>(&config),
-        InputTexts::I32 => run_benchmark_for_index_type::<i32, u32>(&config),
-        InputTexts::Hg38 => run_benchmark_for_index_type::<u32, u32>(&config),
-        InputTexts::DoubleHg38 => run_benchmark_for_index_type::<i64, u64>(&config),
-    };
+    result.unwrap_or_else(|e| {
+        error!("Benchmark for {} failed: {e}", config.library);
 
-    update_stored_results(result, config);
+        let mut result = BenchmarkResult::new_empty(config.clone());
+        result.status = RunStatus::Error(e.to_string());
+        result
+    })
 }
 
-fn run_benchmark_for_index_type<G: genedex::IndexStorage, S: sview_fmindex::Position + 'static>(
-    config: &Config,
-) -> BenchmarkResult {
-    match config.library {
-        Library::GenedexFlat64 => genedex::FmIndexFlat64::<G>::run_benchmark(config),
-        Library::GenedexCond64 => genedex::FmIndexCondensed64::<G>::run_benchmark(config),
-        Library::GenedexCond512 => genedex::FmIndexCondensed512::<G>::run_benchmark(config),
-        Library::BioLarge => bio_bench::BioFmIndex::<32>::run_benchmark(config),
-        Library::BioSmall => bio_bench::BioFmIndex::<2048>::run_benchmark(config),
-        Library::Awry => awry_bench::AwryFmIndex::run_benchmark(config),
-        Library::FmIndexSingle => fmindex_bench::FMIndexCrateSingleFmIndex::run_benchmark(config),
-        Library::FmIndexMulti => fmindex_bench::FMIndexCrateMultiFmIndex::run_benchmark(config),
-        Library::SviewFmIndexVec32 => {
-            sview_fmindex_bench::SViewFMIndex::<S, u32>::run_benchmark(config)
-        }
-        Library::SviewFmIndexVec128 => {
-            sview_fmindex_bench::SViewFMIndex::<S, u128>::run_benchmark(config)
-        }
+fn setup_input_data(config: &Config) -> Result<(), BenchmarkError> {
+    // the other inputs are derived from hg38
+    if config.input_texts.alphabet() != Alphabet::Dna {
+        return Ok(());
     }
-}
 
-fn setup_input_data() {
     let path_chromosome = InputTexts::Chromosome.get_filepath();
     let path_i32 = InputTexts::I32.get_filepath();
     let path_hg38 = InputTexts::Hg38.get_filepath();
@@ -271,7 +785,7 @@ fn setup_input_data() {
     let paths = [&path_chromosome, &path_i32, &path_hg38, &path_double_hg38];
 
     if paths.map(|p| p.exists()).into_iter().all(identity) {
-        return;
+        return Ok(());
     }
 
     info!("Seems to be the first run in this environment. Preparing the different input files...",);
@@ -279,39 +793,57 @@ fn setup_input_data() {
     let chromosome_num_records = 10;
     let i32_num_records = 30;
 
-    let reader = bio::io::fasta::Reader::from_file(path_hg38).unwrap();
+    let reader = bio::io::fasta::Reader::new(InputReader::open(&path_hg38)?);
+
+    let to_file = |path: &PathBuf| {
+        bio::io::fasta::Writer::to_file(path).map_err(|e| BenchmarkError::io(path, e))
+    };
 
-    let mut chromosome_writer = bio::io::fasta::Writer::to_file(path_chromosome).unwrap();
-    let mut i32_writer = bio::io::fasta::Writer::to_file(path_i32).unwrap();
-    let mut double_hg38_writer = bio::io::fasta::Writer::to_file(path_double_hg38).unwrap();
+    let mut chromosome_writer = to_file(&path_chromosome)?;
+    let mut i32_writer = to_file(&path_i32)?;
+    let mut double_hg38_writer = to_file(&path_double_hg38)?;
 
     for (i, record) in reader.records().enumerate() {
-        let record = record.unwrap();
+        let record = record.map_err(|e| BenchmarkError::input(&path_hg38, e))?;
 
         if i < chromosome_num_records {
-            chromosome_writer.write_record(&record).unwrap();
+            chromosome_writer
+                .write_record(&record)
+                .map_err(|e| BenchmarkError::io(&path_chromosome, e))?;
         }
 
         if i < i32_num_records {
-            i32_writer.write_record(&record).unwrap();
+            i32_writer
+                .write_record(&record)
+                .map_err(|e| BenchmarkError::io(&path_i32, e))?;
         }
 
-        double_hg38_writer.write_record(&record).unwrap();
-
         let revcomp = bio::alphabets::dna::revcomp(record.seq());
 
         double_hg38_writer
-            .write(record.id(), record.desc(), &revcomp)
-            .unwrap();
+            .write_record(&record)
+            .and_then(|_| double_hg38_writer.write(record.id(), record.desc(), &revcomp))
+            .map_err(|e| BenchmarkError::io(&path_double_hg38, e))?;
+    }
+
+    // the writers are buffered, so the last write errors only show up here
+    for (writer, path) in [
+        (&mut chromosome_writer, &path_chromosome),
+        (&mut i32_writer, &path_i32),
+        (&mut double_hg38_writer, &path_double_hg38),
+    ] {
+        writer.flush().map_err(|e| BenchmarkError::io(path, e))?;
     }
+
+    Ok(())
 }
 
-fn setup_logger(library: Library) -> Result<(), fern::InitError> {
+fn setup_logger(log_name: &str) -> Result<(), fern::InitError> {
     fern::Dispatch::new()
         .format(|out, message, _| out.finish(format_args!("{}", message)))
         .level(log::LevelFilter::Info)
         .chain(std::io::stdout())
-        .chain(fern::log_file(format!("logs/{}.txt", library))?)
+        .chain(fern::log_file(format!("logs/{log_name}.txt"))?)
         .apply()?;
     Ok(())
 }
@@ -339,12 +871,97 @@ fn update_stored_results(result: BenchmarkResult, config: Config) {
 }
 
 fn key_to_string(config: &Config) -> String {
-    format!(
-        "{}-threads-{}-arg-{}",
+    let mut key = format!(
+        "{}-threads-{}-arg-{}{}",
         config.library,
         config.build_thread_count,
         config
             .extra_build_arg
-            .map_or_else(|| "none".to_string(), |arg| arg.to_string())
-    )
+            .map_or_else(|| "none".to_string(), |arg| arg.to_string()),
+        config.texts_transformation_suffix().replace('_', "-")
+    );
+
+    // only when it was reduced, to keep the keys of older results valid
+    if config.lookup_table_depth() != config.depth_of_lookup_table {
+        key += &format!("-lookup-depth-{}", config.lookup_table_depth());
+    }
+
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the config of the bench subcommand with these arguments
+    pub fn config_from_args(args: &[&str]) -> Config {
+        let cli = Cli::parse_from(["rust-fmindex-benchmark", "bench"].iter().chain(args));
+
+        match cli.command {
+            Command::Bench(config) => *config,
+            Command::Report(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn lookup_table_depth() {
+        for (input_texts, depth_of_lookup_table, lookup_table_depth) in [
+            ("hg38", "13", 13),
+            ("hg38", "10", 10),
+            ("hg38", "14", 13),
+            ("proteome", "13", 6),
+            ("proteome", "4", 4),
+            ("english", "13", 3),
+            ("english", "10", 3),
+        ] {
+            let config = config_from_args(&[
+                "genedex-flat64",
+                "-i",
+                input_texts,
+                "-d",
+                depth_of_lookup_table,
+            ]);
+            assert_eq!(config.lookup_table_depth(), lookup_table_depth);
+
+            let key = key_to_string(&config);
+            assert_eq!(
+                key.ends_with(&format!("-lookup-depth-{lookup_table_depth}")),
+                config.depth_of_lookup_table != lookup_table_depth,
+                "{key}"
+            );
+        }
+    }
+
+    #[test]
+    fn degenerate_policy_from_str() {
+        assert_eq!("drop".parse(), Ok(DegeneratePolicy::Drop));
+        assert_eq!("split".parse(), Ok(DegeneratePolicy::Split));
+        assert_eq!("replace:N".parse(), Ok(DegeneratePolicy::Replace(b'N')));
+        assert_eq!("expand:16".parse(), Ok(DegeneratePolicy::Expand(16)));
+
+        for invalid in [
+            "",
+            "keep",
+            "replace",
+            "replace:",
+            "replace:AC",
+            "expand",
+            "expand:x",
+            "drop:1",
+        ] {
+            assert!(invalid.parse::<DegeneratePolicy>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn degenerate_policy_display_round_trip() {
+        for policy in [
+            DegeneratePolicy::Replace(b'X'),
+            DegeneratePolicy::Drop,
+            DegeneratePolicy::Split,
+            DegeneratePolicy::Expand(64),
+        ] {
+            assert_eq!(policy.to_string().parse(), Ok(policy));
+        }
+    }
 }
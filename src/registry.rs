@@ -0,0 +1,76 @@
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+
+use crate::common_interface::Capabilities;
+use crate::error::BenchmarkError;
+use crate::{BenchmarkResult, Config};
+
+// Every library registers itself here once. The command line interface, the benchmark dispatch, the stored results
+// and the report all use this list. Only the libraries enabled via cargo features are compiled and registered.
+pub const LIBRARIES: &[LibraryInfo] = &[
+    #[cfg(feature = "awry")]
+    crate::awry_bench::LIBRARY,
+    #[cfg(feature = "rust-bio")]
+    crate::bio_bench::LIBRARY,
+    #[cfg(feature = "fm-index")]
+    crate::fmindex_bench::LIBRARY,
+    #[cfg(feature = "genedex")]
+    crate::genedex_bench::LIBRARY,
+    #[cfg(feature = "sview-fmindex")]
+    crate::sview_fmindex_bench::LIBRARY,
+];
+
+pub struct LibraryInfo {
+    pub crate_name: &'static str,
+    pub url: &'static str,
+    pub variants: &'static [Variant],
+}
+
+// a specific configuration of a library that is benchmarked, for example a specific index type
+pub struct Variant {
+    // kebab-case name used on the command line
+    pub name: &'static str,
+    pub display_name: &'static str,
+    pub color: &'static str,
+    pub color_with_threads: &'static str,
+    pub capabilities: Capabilities,
+    pub run_benchmark: fn(&Config) -> Result<BenchmarkResult, BenchmarkError>,
+}
+
+impl Variant {
+    // the identifier used in the stored results, index files and logs, e.g. GenedexFlat64 for genedex-flat64
+    pub fn id(&self) -> String {
+        self.name
+            .split('-')
+            .map(|part| {
+                let mut chars = part.chars();
+                chars.next().map_or_else(String::new, |first| {
+                    first.to_ascii_uppercase().to_string() + chars.as_str()
+                })
+            })
+            .collect()
+    }
+}
+
+pub fn variants() -> impl Iterator<Item = (&'static LibraryInfo, &'static Variant)> {
+    LIBRARIES.iter().flat_map(|library| {
+        library
+            .variants
+            .iter()
+            .map(move |variant| (library, variant))
+    })
+}
+
+pub fn find(id: &str) -> Option<(&'static LibraryInfo, &'static Variant)> {
+    variants().find(|(_, variant)| variant.id() == id)
+}
+
+// accepts the kebab-case names of all registered variants and yields their ids
+pub fn library_value_parser() -> impl TypedValueParser<Value = String> {
+    PossibleValuesParser::new(variants().map(|(_, variant)| variant.name)).map(|name| {
+        variants()
+            .find(|(_, variant)| variant.name == name)
+            .unwrap()
+            .1
+            .id()
+    })
+}
@@ -0,0 +1,1062 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, ValueEnum};
+use log::info;
+
+use crate::common_interface::{Capabilities, FileIoSupport};
+use crate::error::BenchmarkError;
+use crate::registry::{self, LIBRARIES};
+use crate::{
+    Alphabet, BenchmarkResult, DEFAULT_QUERIES_DEGENERATE_POLICY, ExtraBuildArg, InputTexts,
+    QueryOrder, SearchConfig, SearchMode,
+};
+
+#[derive(Debug, Args, Clone)]
+pub struct ReportConfig {
+    #[arg(short, long, default_value = "results")]
+    results_dir: PathBuf,
+
+    #[arg(short, long, default_value = "plots")]
+    output_dir: PathBuf,
+
+    // the generated sections between the report markers are replaced, the rest of the file is kept
+    #[arg(long, default_value = "README.md")]
+    readme_path: PathBuf,
+
+    // the bar charts use a single query length, the query length sweeps are plotted separately
+    #[arg(short, long, default_value_t = 50)]
+    length_of_queries: usize,
+
+    #[arg(short, long, default_value = "hg38")]
+    memory_factor_input_texts: InputTexts,
+}
+
+pub fn generate_report(config: &ReportConfig) -> Result<(), BenchmarkError> {
+    let readme = std::fs::read_to_string(&config.readme_path)
+        .map_err(|e| BenchmarkError::io(&config.readme_path, e))?;
+
+    let img_dir = config.output_dir.join("img");
+    std::fs::create_dir_all(&img_dir).map_err(|e| BenchmarkError::io(&img_dir, e))?;
+
+    let mut feature_table = String::new();
+    write_feature_table(&mut feature_table, config)?;
+    let readme = replace_generated_section(&readme, FEATURE_TABLE_SECTION, &feature_table)
+        .map_err(|e| BenchmarkError::input(&config.readme_path, e))?;
+
+    let mut library_links = String::new();
+    for library in LIBRARIES {
+        let _ = writeln!(library_links, "[`{}`]: {}", library.crate_name, library.url);
+    }
+    let readme = replace_generated_section(&readme, LIBRARY_LINKS_SECTION, &library_links)
+        .map_err(|e| BenchmarkError::input(&config.readme_path, e))?;
+
+    // the plots that are already shown with a description in the rest of the README are not repeated
+    let readme = replace_generated_section(&readme, RESULTS_SECTION, "")
+        .map_err(|e| BenchmarkError::input(&config.readme_path, e))?;
+
+    let mut markdown = String::new();
+
+    for &input_texts in InputTexts::value_variants() {
+        let results_filepath = config.results_dir.join(format!("{input_texts}.json"));
+
+        if !std::fs::exists(&results_filepath)
+            .map_err(|e| BenchmarkError::io(&results_filepath, e))?
+        {
+            continue;
+        }
+
+        let results = read_sorted_results(&results_filepath)?;
+
+        let _ = writeln!(markdown, "### All Results for `{input_texts}`\n");
+
+        let duo_charts = PlotKind::ALL.into_iter().filter_map(|plot_kind| {
+            plot_kind
+                .duo_chart(&results, config.length_of_queries)
+                .map(|chart| (plot_kind.name().to_string(), chart.to_svg()))
+        });
+        let query_length_charts = [SearchMode::Count, SearchMode::Locate]
+            .into_iter()
+            .filter_map(|search_mode| {
+                query_length_chart(&results, search_mode)
+                    .map(|chart| (format!("{search_mode:?}ByQueryLength"), chart.to_svg()))
+            });
+
+        for (name, svg) in duo_charts.chain(query_length_charts) {
+            let name = format!("{name}-{input_texts}");
+            let svg_filepath = img_dir.join(format!("{name}.svg"));
+            std::fs::write(&svg_filepath, svg).map_err(|e| BenchmarkError::io(&svg_filepath, e))?;
+            info!("Wrote {}", svg_filepath.display());
+
+            // relative to the README, with forward slashes also on Windows
+            let src = format!("{}/img/{name}.svg", config.output_dir.display());
+            if !readme.contains(&format!("<img src=\"{src}\" />")) {
+                let _ = writeln!(markdown, "<img src=\"{src}\" />\n");
+            }
+        }
+
+        write_results_table(&mut markdown, &results);
+    }
+
+    let readme = replace_generated_section(&readme, RESULTS_SECTION, &markdown)
+        .map_err(|e| BenchmarkError::input(&config.readme_path, e))?;
+
+    std::fs::write(&config.readme_path, readme)
+        .map_err(|e| BenchmarkError::io(&config.readme_path, e))?;
+    info!("Updated {}", config.readme_path.display());
+
+    Ok(())
+}
+
+const FEATURE_TABLE_SECTION: &str = "feature-table";
+const LIBRARY_LINKS_SECTION: &str = "library-links";
+const RESULTS_SECTION: &str = "results";
+
+// The generated content is placed between the markers <!-- report:name --> and <!-- /report:name -->, which are kept.
+fn replace_generated_section(readme: &str, name: &str, content: &str) -> Result<String, String> {
+    let start_marker = format!("<!-- report:{name} -->\n");
+    let end_marker = format!("<!-- /report:{name} -->");
+
+    let start = readme
+        .find(&start_marker)
+        .ok_or_else(|| format!("missing the marker {}", start_marker.trim_end()))?
+        + start_marker.len();
+    let end = readme[start..]
+        .find(&end_marker)
+        .ok_or_else(|| format!("missing the marker {end_marker}"))?
+        + start;
+
+    Ok(format!("{}{content}{}", &readme[..start], &readme[end..]))
+}
+
+fn read_sorted_results(path: &Path) -> Result<Vec<(String, BenchmarkResult)>, BenchmarkError> {
+    let file = File::open(path).map_err(|e| BenchmarkError::io(path, e))?;
+    let results: HashMap<String, BenchmarkResult> = serde_json::from_reader(BufReader::new(file))
+        .map_err(|e| BenchmarkError::input(path, e))?;
+
+    let mut results: Vec<_> = results.into_iter().collect();
+    results.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    Ok(results)
+}
+
+fn extra_build_arg_name(arg: ExtraBuildArg) -> &'static str {
+    match arg {
+        ExtraBuildArg::LowMemory => "low memory",
+        ExtraBuildArg::MediumMemory => "medium memory",
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PlotKind {
+    Construction,
+    FileIo,
+    Count,
+    Locate,
+}
+
+#[derive(Clone, Copy)]
+enum Metric {
+    ConstructionTime,
+    ConstructionPeakMemory,
+    WriteTime,
+    ReadTime,
+    SearchTime(SearchMode),
+    IndexMemory,
+}
+
+impl PlotKind {
+    const ALL: [PlotKind; 4] = [
+        PlotKind::Construction,
+        PlotKind::FileIo,
+        PlotKind::Count,
+        PlotKind::Locate,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            PlotKind::Construction => "Construction",
+            PlotKind::FileIo => "FileIO",
+            PlotKind::Count => "Count",
+            PlotKind::Locate => "Locate",
+        }
+    }
+
+    fn metrics(self) -> (Metric, Metric) {
+        match self {
+            PlotKind::Construction => (Metric::ConstructionTime, Metric::ConstructionPeakMemory),
+            PlotKind::FileIo => (Metric::WriteTime, Metric::ReadTime),
+            PlotKind::Count => (Metric::SearchTime(SearchMode::Count), Metric::IndexMemory),
+            PlotKind::Locate => (Metric::SearchTime(SearchMode::Locate), Metric::IndexMemory),
+        }
+    }
+
+    fn duo_chart(
+        self,
+        results: &[(String, BenchmarkResult)],
+        length_of_queries: usize,
+    ) -> Option<DuoChart> {
+        let (left_metric, right_metric) = self.metrics();
+
+        // to filter out different build configs for plots other than construction
+        let mut existing_libraries = Vec::new();
+        let mut entries = Vec::new();
+
+        for (_, result) in results {
+            if registry::find(&result.config.library).is_none() {
+                continue;
+            }
+
+            let left = left_metric.extract(result, length_of_queries);
+            let right = right_metric.extract(result, length_of_queries);
+
+            let (Some(left), Some(right)) = (left, right) else {
+                continue;
+            };
+
+            let library = &result.config.library;
+            if self != PlotKind::Construction && existing_libraries.contains(&library) {
+                continue;
+            }
+            existing_libraries.push(library);
+
+            entries.push(self.chart_entry(result, left, right));
+        }
+
+        if entries.is_empty() {
+            return None;
+        }
+
+        Some(DuoChart {
+            left_title: left_metric.title(),
+            left_unit: left_metric.unit(),
+            right_title: right_metric.title(),
+            right_unit: right_metric.unit(),
+            entries,
+        })
+    }
+
+    fn chart_entry(self, result: &BenchmarkResult, left: f64, right: f64) -> ChartEntry {
+        let config = &result.config;
+        let (_, variant) = registry::find(&config.library).unwrap();
+
+        let mut name = variant.display_name.to_string();
+        let mut hatch = Hatch::None;
+
+        if self == PlotKind::Construction {
+            if config.build_thread_count > 1 {
+                name += &format!(", {} threads", config.build_thread_count);
+            }
+
+            if let Some(arg) = config.extra_build_arg {
+                name += &format!(", {}", extra_build_arg_name(arg));
+
+                hatch = match arg {
+                    ExtraBuildArg::LowMemory => Hatch::Diagonal,
+                    ExtraBuildArg::MediumMemory => Hatch::Cross,
+                };
+            }
+        }
+
+        let color = if self == PlotKind::Construction && config.build_thread_count == 1 {
+            variant.color
+        } else {
+            variant.color_with_threads
+        };
+
+        ChartEntry {
+            name,
+            color,
+            hatch,
+            left,
+            right,
+        }
+    }
+}
+
+impl Metric {
+    fn extract(self, result: &BenchmarkResult, length_of_queries: usize) -> Option<f64> {
+        match self {
+            Metric::ConstructionTime => result.construction_time_secs,
+            Metric::ConstructionPeakMemory => result
+                .construction_peak_memory_usage_mb
+                .map(|mb| mb / 1_000.0),
+            Metric::WriteTime => result.write_to_file_time_secs,
+            Metric::ReadTime => result.read_from_file_time_secs,
+            Metric::SearchTime(search_mode) => {
+                let search_config = SearchConfig {
+                    search_mode,
+                    num_queries_records: None,
+                    length_of_queries: Some(length_of_queries),
+                    queries_degenerate_policy: DEFAULT_QUERIES_DEGENERATE_POLICY,
+                    query_seed: None,
+                    query_order: QueryOrder::File,
+                    stream_queries: None,
+                    both_strands: false,
+                    first_k: None,
+                    max_errors: None,
+                    edit_pruning: None,
+                    min_seed_length: None,
+                };
+
+                // for now only go with min running times
+                result
+                    .search_metrics
+                    .get(&search_config.to_string())
+                    .map(|m| m.min_time_secs)
+            }
+            Metric::IndexMemory => result.only_index_in_memory_size_mb.map(|mb| mb / 1_000.0),
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Metric::ConstructionTime => "running time",
+            Metric::ConstructionPeakMemory => "peak memory usage",
+            Metric::WriteTime => "write running time",
+            Metric::ReadTime => "read running time",
+            Metric::SearchTime(_) => "running time",
+            Metric::IndexMemory => "index memory usage",
+        }
+    }
+
+    fn unit(self) -> &'static str {
+        match self {
+            Metric::ConstructionPeakMemory | Metric::IndexMemory => "gigabytes",
+            _ => "seconds",
+        }
+    }
+}
+
+// One line per library and sweep, the sweeps of a library can differ for example in the number of queries. The
+// query lengths are stored with the results of the searches, because the sweeps can be extended by later runs.
+fn query_length_chart(
+    results: &[(String, BenchmarkResult)],
+    search_mode: SearchMode,
+) -> Option<LineChart> {
+    let mut existing_libraries = Vec::new();
+    let mut series = Vec::new();
+
+    for (_, result) in results {
+        let Some((_, variant)) = registry::find(&result.config.library) else {
+            continue;
+        };
+
+        if existing_libraries.contains(&&result.config.library) {
+            continue;
+        }
+
+        let mut sweeps: BTreeMap<String, Vec<(usize, f64)>> = BTreeMap::new();
+
+        for (key, metrics) in &result.search_metrics {
+            // the soft mask and strand results of the sweep have other keys
+            let Some(search_config) = metrics.swept_search_config else {
+                continue;
+            };
+            if search_config.search_mode != search_mode || *key != search_config.to_string() {
+                continue;
+            }
+
+            let (Some(length_of_queries), Some(num_queries)) = (
+                search_config.length_of_queries,
+                metrics.num_queries.filter(|&n| n > 0),
+            ) else {
+                continue;
+            };
+
+            let sweep = SearchConfig {
+                length_of_queries: None,
+                ..search_config
+            };
+            sweeps.entry(sweep.to_string()).or_default().push((
+                length_of_queries,
+                metrics.min_time_secs * 1_000_000.0 / num_queries as f64,
+            ));
+        }
+
+        if sweeps.is_empty() {
+            continue;
+        }
+
+        existing_libraries.push(&result.config.library);
+
+        let num_sweeps = sweeps.len();
+        for (sweep, mut points) in sweeps {
+            points.sort_by_key(|&(length_of_queries, _)| length_of_queries);
+
+            let name = if num_sweeps == 1 {
+                variant.display_name.to_string()
+            } else {
+                format!("{} ({sweep})", variant.display_name)
+            };
+
+            series.push(LineSeries {
+                name,
+                color: variant.color_with_threads,
+                points,
+            });
+        }
+    }
+
+    if series.is_empty() {
+        return None;
+    }
+
+    Some(LineChart {
+        title: format!("{search_mode:?} running time per query in microseconds"),
+        series,
+    })
+}
+
+// ---------- markdown output ----------
+
+// construction memory usage is considered good if it is at most this many times the size of the texts
+const GOOD_MEMORY_FACTOR: f64 = 10.0;
+
+struct FeatureRow {
+    crate_name: &'static str,
+    capabilities: Capabilities,
+    memory_factors: Vec<f64>,
+}
+
+fn write_feature_table(markdown: &mut String, config: &ReportConfig) -> Result<(), BenchmarkError> {
+    // a crate supports a feature, if any of its benchmarked variants supports it
+    let mut rows: Vec<_> = LIBRARIES
+        .iter()
+        .map(|library| FeatureRow {
+            crate_name: library.crate_name,
+            capabilities: library
+                .variants
+                .iter()
+                .map(|variant| variant.capabilities)
+                .reduce(merge_capabilities)
+                .unwrap(),
+            memory_factors: Vec::new(),
+        })
+        .collect();
+
+    let input_texts = config.memory_factor_input_texts;
+    let results_filepath = config.results_dir.join(format!("{input_texts}.json"));
+
+    if std::fs::exists(&results_filepath).map_err(|e| BenchmarkError::io(&results_filepath, e))? {
+        let results = read_sorted_results(&results_filepath)?;
+
+        // the size of the texts doesn't depend on the library
+        let texts_size_mb = results
+            .iter()
+            .filter_map(|(_, result)| result.texts_size_mb)
+            .fold(None, |acc: Option<f64>, size| {
+                Some(acc.map_or(size, |a| a.max(size)))
+            });
+
+        if let Some(texts_size_mb) = texts_size_mb {
+            for (_, result) in &results {
+                let Some(peak_memory_usage_mb) = result.construction_peak_memory_usage_mb else {
+                    continue;
+                };
+
+                let Some((library, _)) = registry::find(&result.config.library) else {
+                    continue;
+                };
+
+                let row = rows
+                    .iter_mut()
+                    .find(|row| row.crate_name == library.crate_name)
+                    .unwrap();
+
+                row.memory_factors
+                    .push(peak_memory_usage_mb / texts_size_mb);
+            }
+        }
+    }
+
+    rows.sort_by_key(|row| row.crate_name);
+
+    let _ = write!(
+        markdown,
+        "The construction memory usage is given as the peak memory usage during construction for the `{input_texts}` \
+        input, relative to the size of the texts.\n\n\
+        | **Library** | **Good construction memory usage** | **Multiple texts** |  **Disk I/O** | **Disk I/O `mmap`** |**Multithreaded construction** | **Backward search cursor** | **Protein alphabet** | **Byte alphabet** |\n\
+        | ----------- | :-------------: | :-------------: | :-------------: | :-------------: |  :-------------: | :-------------: | :-------------: | :-------------: |\n"
+    );
+
+    let mut depends_on_build_config = false;
+
+    for row in &rows {
+        let memory = format_memory_factors(&row.memory_factors);
+        depends_on_build_config |= memory.ends_with('*');
+
+        let capabilities = row.capabilities;
+        let _ = writeln!(
+            markdown,
+            "| [`{}`] | {memory} | {} | {} | {} | {} | {} | {} | {} |",
+            row.crate_name,
+            format_flag(capabilities.multiple_texts),
+            match capabilities.file_io {
+                FileIoSupport::Unsupported => "❌",
+                FileIoSupport::VerySlow | FileIoSupport::Slow => "⚠️",
+                FileIoSupport::Fast => "✅",
+            },
+            format_flag(capabilities.file_io_mmap),
+            format_flag(capabilities.multithreaded_construction),
+            format_flag(capabilities.cursor),
+            format_flag(capabilities.alphabets.contains(&Alphabet::Protein)),
+            format_flag(capabilities.alphabets.contains(&Alphabet::Bytes)),
+        );
+    }
+
+    markdown.push('\n');
+
+    if depends_on_build_config {
+        markdown.push_str("*: depending on the build configuration.\n\n");
+    }
+
+    Ok(())
+}
+
+fn merge_capabilities(a: Capabilities, b: Capabilities) -> Capabilities {
+    Capabilities {
+        multiple_texts: a.multiple_texts || b.multiple_texts,
+        file_io: a.file_io.max(b.file_io),
+        file_io_mmap: a.file_io_mmap || b.file_io_mmap,
+        multithreaded_construction: a.multithreaded_construction || b.multithreaded_construction,
+        locate: a.locate || b.locate,
+        cursor: a.cursor || b.cursor,
+        reads_texts_from_file: a.reads_texts_from_file && b.reads_texts_from_file,
+        // the variants of a library usually support the same alphabets
+        alphabets: if a.alphabets.len() >= b.alphabets.len() {
+            a.alphabets
+        } else {
+            b.alphabets
+        },
+    }
+}
+
+fn format_flag(flag: bool) -> &'static str {
+    if flag { "✅" } else { "❌" }
+}
+
+fn format_memory_factors(factors: &[f64]) -> String {
+    if factors.is_empty() {
+        return String::from("❓");
+    }
+
+    let min = factors
+        .iter()
+        .copied()
+        .fold(f64::INFINITY, f64::min)
+        .round();
+    let max = factors.iter().copied().fold(0.0, f64::max).round();
+
+    let symbol = format_flag(min <= GOOD_MEMORY_FACTOR);
+
+    if min == max {
+        format!("{symbol} ({min}x)")
+    } else {
+        format!("{symbol} ({min}-{max}x)*")
+    }
+}
+
+fn write_results_table(markdown: &mut String, results: &[(String, BenchmarkResult)]) {
+    let search_configs: BTreeSet<&String> = results
+        .iter()
+        .flat_map(|(_, result)| result.search_metrics.keys())
+        .collect();
+
+    markdown.push_str(
+        "| **Library** | **Threads** | **Build arg** | **Construction (s)** | **Peak memory (GB)** | **Index memory (GB)** |",
+    );
+    for search_config in &search_configs {
+        let _ = write!(markdown, " **{search_config} (s)** |");
+    }
+    markdown.push_str(" **Write (s)** | **Read (s)** | **Status** |\n");
+
+    markdown.push_str("| ----------- | --: | --- | --: | --: | --: |");
+    for _ in &search_configs {
+        markdown.push_str(" --: |");
+    }
+    markdown.push_str(" --: | --: | --- |\n");
+
+    for (_, result) in results {
+        let config = &result.config;
+
+        let Some((_, variant)) = registry::find(&config.library) else {
+            continue;
+        };
+
+        let _ = write!(
+            markdown,
+            "| {} | {} | {} | {} | {} | {} |",
+            variant.display_name,
+            config.build_thread_count,
+            config.extra_build_arg.map_or("-", extra_build_arg_name),
+            format_optional(result.construction_time_secs),
+            format_optional(
+                result
+                    .construction_peak_memory_usage_mb
+                    .map(|mb| mb / 1_000.0)
+            ),
+            format_optional(result.only_index_in_memory_size_mb.map(|mb| mb / 1_000.0)),
+        );
+
+        for &search_config in &search_configs {
+            let time = result
+                .search_metrics
+                .get(search_config)
+                .map(|m| m.min_time_secs);
+            let _ = write!(markdown, " {} |", format_optional(time));
+        }
+
+        let _ = writeln!(
+            markdown,
+            " {} | {} | {} |",
+            format_optional(result.write_to_file_time_secs),
+            format_optional(result.read_from_file_time_secs),
+            result.status,
+        );
+    }
+
+    markdown.push('\n');
+}
+
+fn format_optional(value: Option<f64>) -> String {
+    value.map_or_else(|| String::from("-"), |v| format!("{v:.1}"))
+}
+
+// ---------- svg output, modeled after the previous matplotlib plots ----------
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Hatch {
+    None,
+    Diagonal,
+    Cross,
+}
+
+struct ChartEntry {
+    name: String,
+    color: &'static str,
+    hatch: Hatch,
+    left: f64,
+    right: f64,
+}
+
+struct DuoChart {
+    left_title: &'static str,
+    left_unit: &'static str,
+    right_title: &'static str,
+    right_unit: &'static str,
+    entries: Vec<ChartEntry>,
+}
+
+const WIDTH: f64 = 1200.0;
+const PANEL_WIDTH: f64 = 500.0;
+const PANEL_GAP: f64 = 100.0;
+const MARGIN: f64 = 50.0;
+const LEGEND_COLUMNS: usize = 3;
+const LEGEND_ROW_HEIGHT: f64 = 26.0;
+const PANELS_HEIGHT: f64 = 480.0;
+
+impl DuoChart {
+    fn to_svg(&self) -> String {
+        let legend_rows = self.entries.len().div_ceil(LEGEND_COLUMNS);
+        let legend_height = legend_rows as f64 * LEGEND_ROW_HEIGHT + 20.0;
+        let panels_top = legend_height + 2.0 * MARGIN;
+        let height = panels_top + PANELS_HEIGHT + 2.0 * MARGIN;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height:.1}" viewBox="0 0 {WIDTH} {height:.1}" font-family="DejaVu Sans, sans-serif" font-size="14">"#
+        );
+        svg.push_str(concat!(
+            "<defs>\n",
+            r#"<pattern id="hatch-diagonal" patternUnits="userSpaceOnUse" width="8" height="8">"#,
+            r#"<path d="M-2,2 l4,-4 M0,8 l8,-8 M6,10 l4,-4" stroke="black" stroke-width="1"/></pattern>"#,
+            "\n",
+            r#"<pattern id="hatch-cross" patternUnits="userSpaceOnUse" width="8" height="8">"#,
+            r#"<path d="M4,0 v8 M0,4 h8" stroke="black" stroke-width="1"/></pattern>"#,
+            "\n</defs>\n",
+        ));
+        let _ = writeln!(
+            svg,
+            r#"<rect width="{WIDTH}" height="{height:.1}" fill="white"/>"#
+        );
+
+        self.write_legend(&mut svg);
+
+        let left_values: Vec<_> = self.entries.iter().map(|e| e.left).collect();
+        let right_values: Vec<_> = self.entries.iter().map(|e| e.right).collect();
+
+        self.write_panel(
+            &mut svg,
+            MARGIN,
+            panels_top,
+            self.left_title,
+            self.left_unit,
+            &left_values,
+        );
+        self.write_panel(
+            &mut svg,
+            MARGIN + PANEL_WIDTH + PANEL_GAP,
+            panels_top,
+            self.right_title,
+            self.right_unit,
+            &right_values,
+        );
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn write_legend(&self, svg: &mut String) {
+        let column_width = (WIDTH - 2.0 * MARGIN) / LEGEND_COLUMNS as f64;
+
+        for (i, entry) in self.entries.iter().enumerate() {
+            let x = MARGIN + (i % LEGEND_COLUMNS) as f64 * column_width;
+            let y = MARGIN + (i / LEGEND_COLUMNS) as f64 * LEGEND_ROW_HEIGHT;
+
+            write_bar_rect(svg, x, y, 30.0, 16.0, entry.color, entry.hatch);
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}">{}</text>"#,
+                x + 40.0,
+                y + 13.0,
+                escape_xml(&entry.name)
+            );
+        }
+    }
+
+    fn write_panel(
+        &self,
+        svg: &mut String,
+        x: f64,
+        y: f64,
+        title: &str,
+        unit: &str,
+        values: &[f64],
+    ) {
+        let max_value = values.iter().copied().fold(0.0, f64::max);
+        let step = nice_tick_step(max_value);
+        // leave some space for the bar labels
+        let axis_max = (max_value * 1.15).max(step);
+        let scale = PANEL_WIDTH / axis_max;
+
+        let slot_height = PANELS_HEIGHT / values.len() as f64;
+        let bar_height = slot_height * 0.8;
+
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle" font-size="16">{title} in {unit}</text>"#,
+            x + PANEL_WIDTH / 2.0,
+            y - 15.0,
+        );
+
+        let mut tick = 0.0;
+        while tick <= axis_max {
+            let tick_x = x + tick * scale;
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{tick_x:.1}" y1="{:.1}" x2="{tick_x:.1}" y2="{:.1}" stroke="black"/>"#,
+                y + PANELS_HEIGHT,
+                y + PANELS_HEIGHT + 5.0,
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{tick_x:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+                y + PANELS_HEIGHT + 20.0,
+                format_tick(tick, step),
+            );
+            tick += step;
+        }
+
+        for (i, (&value, entry)) in values.iter().zip(&self.entries).enumerate() {
+            let bar_y = y + i as f64 * slot_height + (slot_height - bar_height) / 2.0;
+            let bar_width = value * scale;
+
+            write_bar_rect(
+                svg,
+                x,
+                bar_y,
+                bar_width,
+                bar_height,
+                entry.color,
+                entry.hatch,
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" dominant-baseline="middle">{value:.1}</text>"#,
+                x + bar_width + 4.0,
+                bar_y + bar_height / 2.0,
+            );
+        }
+
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{x:.1}" y="{y:.1}" width="{PANEL_WIDTH}" height="{PANELS_HEIGHT}" fill="none" stroke="black"/>"#
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{unit}</text>"#,
+            x + PANEL_WIDTH / 2.0,
+            y + PANELS_HEIGHT + 42.0,
+        );
+    }
+}
+
+struct LineSeries {
+    name: String,
+    color: &'static str,
+    // query length and running time
+    points: Vec<(usize, f64)>,
+}
+
+struct LineChart {
+    title: String,
+    series: Vec<LineSeries>,
+}
+
+const LINE_PLOT_HEIGHT: f64 = 400.0;
+
+impl LineChart {
+    fn to_svg(&self) -> String {
+        let legend_rows = self.series.len().div_ceil(LEGEND_COLUMNS);
+        let legend_height = legend_rows as f64 * LEGEND_ROW_HEIGHT + 20.0;
+        let plot_top = legend_height + 2.0 * MARGIN;
+        let height = plot_top + LINE_PLOT_HEIGHT + 2.0 * MARGIN;
+
+        let x = 2.0 * MARGIN;
+        let plot_width = WIDTH - 3.0 * MARGIN;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height:.1}" viewBox="0 0 {WIDTH} {height:.1}" font-family="DejaVu Sans, sans-serif" font-size="14">"#
+        );
+        let _ = writeln!(
+            svg,
+            r#"<rect width="{WIDTH}" height="{height:.1}" fill="white"/>"#
+        );
+
+        self.write_legend(&mut svg);
+
+        let lengths: BTreeSet<usize> = self
+            .series
+            .iter()
+            .flat_map(|s| s.points.iter().map(|&(length, _)| length))
+            .collect();
+        let min_length = *lengths.first().unwrap() as f64;
+        let max_length = *lengths.last().unwrap() as f64;
+        // a single query length is drawn in the middle
+        let length_range = (max_length - min_length).max(1.0);
+        let x_of = |length: usize| {
+            if lengths.len() == 1 {
+                x + plot_width / 2.0
+            } else {
+                x + (length as f64 - min_length) / length_range * plot_width
+            }
+        };
+
+        let max_value = self
+            .series
+            .iter()
+            .flat_map(|s| s.points.iter().map(|&(_, value)| value))
+            .fold(0.0, f64::max);
+        let step = nice_tick_step(max_value);
+        let axis_max = (max_value * 1.1).max(step);
+        let y_of = |value: f64| plot_top + LINE_PLOT_HEIGHT - value / axis_max * LINE_PLOT_HEIGHT;
+
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle" font-size="16">{}</text>"#,
+            x + plot_width / 2.0,
+            plot_top - 15.0,
+            escape_xml(&self.title),
+        );
+
+        let mut tick = 0.0;
+        while tick <= axis_max {
+            let tick_y = y_of(tick);
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{:.1}" y1="{tick_y:.1}" x2="{x:.1}" y2="{tick_y:.1}" stroke="black"/>"#,
+                x - 5.0,
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{tick_y:.1}" text-anchor="end" dominant-baseline="middle">{}</text>"#,
+                x - 8.0,
+                format_tick(tick, step),
+            );
+            tick += step;
+        }
+
+        for &length in &lengths {
+            let tick_x = x_of(length);
+            let bottom = plot_top + LINE_PLOT_HEIGHT;
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{tick_x:.1}" y1="{bottom:.1}" x2="{tick_x:.1}" y2="{:.1}" stroke="black"/>"#,
+                bottom + 5.0,
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{tick_x:.1}" y="{:.1}" text-anchor="middle">{length}</text>"#,
+                bottom + 20.0,
+            );
+        }
+
+        for series in &self.series {
+            let points: Vec<_> = series
+                .points
+                .iter()
+                .map(|&(length, value)| format!("{:.1},{:.1}", x_of(length), y_of(value)))
+                .collect();
+
+            let _ = writeln!(
+                svg,
+                r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+                points.join(" "),
+                series.color,
+            );
+
+            for &(length, value) in &series.points {
+                let _ = writeln!(
+                    svg,
+                    r#"<circle cx="{:.1}" cy="{:.1}" r="4" fill="{}"/>"#,
+                    x_of(length),
+                    y_of(value),
+                    series.color,
+                );
+            }
+        }
+
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{x:.1}" y="{plot_top:.1}" width="{plot_width:.1}" height="{LINE_PLOT_HEIGHT}" fill="none" stroke="black"/>"#
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">query length</text>"#,
+            x + plot_width / 2.0,
+            plot_top + LINE_PLOT_HEIGHT + 42.0,
+        );
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn write_legend(&self, svg: &mut String) {
+        let column_width = (WIDTH - 2.0 * MARGIN) / LEGEND_COLUMNS as f64;
+
+        for (i, series) in self.series.iter().enumerate() {
+            let x = MARGIN + (i % LEGEND_COLUMNS) as f64 * column_width;
+            let y = MARGIN + (i / LEGEND_COLUMNS) as f64 * LEGEND_ROW_HEIGHT;
+
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{x:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="2"/>"#,
+                y + 8.0,
+                x + 30.0,
+                y + 8.0,
+                series.color,
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}">{}</text>"#,
+                x + 40.0,
+                y + 13.0,
+                escape_xml(&series.name)
+            );
+        }
+    }
+}
+
+fn write_bar_rect(
+    svg: &mut String,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    color: &str,
+    hatch: Hatch,
+) {
+    let _ = writeln!(
+        svg,
+        r#"<rect x="{x:.1}" y="{y:.1}" width="{width:.1}" height="{height:.1}" fill="{color}"/>"#
+    );
+
+    let pattern_id = match hatch {
+        Hatch::None => return,
+        Hatch::Diagonal => "hatch-diagonal",
+        Hatch::Cross => "hatch-cross",
+    };
+
+    let _ = writeln!(
+        svg,
+        r#"<rect x="{x:.1}" y="{y:.1}" width="{width:.1}" height="{height:.1}" fill="url(#{pattern_id})"/>"#
+    );
+}
+
+// a step of 1, 2 or 5 times a power of 10 that results in roughly 5 ticks
+fn nice_tick_step(max_value: f64) -> f64 {
+    if max_value <= 0.0 {
+        return 1.0;
+    }
+
+    let rough_step = max_value / 5.0;
+    let magnitude = 10f64.powf(rough_step.log10().floor());
+
+    let normalized = rough_step / magnitude;
+    let nice = if normalized < 1.5 {
+        1.0
+    } else if normalized < 3.5 {
+        2.0
+    } else if normalized < 7.5 {
+        5.0
+    } else {
+        10.0
+    };
+
+    nice * magnitude
+}
+
+fn format_tick(tick: f64, step: f64) -> String {
+    if step >= 1.0 {
+        format!("{tick:.0}")
+    } else {
+        let decimals = (-step.log10().floor()) as usize;
+        format!("{tick:.decimals$}")
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace_generated_section_keeps_the_markers() {
+        let readme = "# Title\n<!-- report:table -->\nold\n<!-- /report:table -->\nrest\n";
+
+        let readme = replace_generated_section(readme, "table", "new\nlines\n").unwrap();
+        assert_eq!(
+            readme,
+            "# Title\n<!-- report:table -->\nnew\nlines\n<!-- /report:table -->\nrest\n"
+        );
+
+        assert_eq!(
+            replace_generated_section(&readme, "table", "new\nlines\n").unwrap(),
+            readme
+        );
+        assert!(replace_generated_section(&readme, "plots", "").is_err());
+        assert!(replace_generated_section("<!-- report:table -->\n", "table", "").is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use std::ops::Range;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::Config;
+use crate::common_interface::HitPosition;
+use crate::compression::InputReader;
+use crate::error::BenchmarkError;
+
+// Lowercase symbols in reference genomes like hg38 mark repeats (soft-masking). The mask is kept as a list of
+// intervals per text, while the index is built from the uppercased texts.
+pub struct SoftMask {
+    // the start of each text in the concatenation of all texts without separators
+    text_starts: Vec<usize>,
+    // sorted and non-overlapping
+    masked_intervals: Vec<Vec<Range<usize>>>,
+}
+
+// a query is counted as repeat-derived, if the majority of its hits is in masked regions
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct SoftMaskStats {
+    pub num_repeat_queries: usize,
+    pub num_unique_queries: usize,
+    // neither repeat-derived nor unique
+    #[serde(default)]
+    pub num_no_hit_queries: usize,
+    pub num_masked_hits: usize,
+    pub num_unmasked_hits: usize,
+}
+
+impl SoftMask {
+    pub fn read(config: &Config) -> Result<Self, BenchmarkError> {
+        let path = config.input_texts.get_filepath();
+        let reader = bio::io::fasta::Reader::new(InputReader::open(&path)?);
+
+        let mut text_starts = Vec::new();
+        let mut masked_intervals = Vec::new();
+        let mut total_len = 0;
+
+        for record in reader.records() {
+            let record = record.map_err(|e| BenchmarkError::input(&path, e))?;
+            let seq = record.seq();
+
+            let mut intervals = Vec::new();
+            let mut start = None;
+
+            for (i, symbol) in seq.iter().enumerate() {
+                match (symbol.is_ascii_lowercase(), start) {
+                    (true, None) => start = Some(i),
+                    (false, Some(s)) => {
+                        intervals.push(s..i);
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(s) = start {
+                intervals.push(s..seq.len());
+            }
+
+            text_starts.push(total_len);
+            masked_intervals.push(intervals);
+            total_len += seq.len();
+        }
+
+        let soft_mask = Self {
+            text_starts,
+            masked_intervals,
+        };
+
+        info!(
+            "Soft-masked symbols: {:.1}% of the texts",
+            soft_mask.num_masked_symbols() as f64 / total_len as f64 * 100.0
+        );
+
+        Ok(soft_mask)
+    }
+
+    pub fn is_masked(&self, hit: HitPosition) -> bool {
+        let (text_id, position) = self.to_text_position(hit);
+        let intervals = &self.masked_intervals[text_id];
+
+        let i = intervals.partition_point(|interval| interval.end <= position);
+        i < intervals.len() && intervals[i].start <= position
+    }
+
+    fn to_text_position(&self, hit: HitPosition) -> (usize, usize) {
+        match hit {
+            HitPosition::InText { text_id, position } => (text_id, position),
+            HitPosition::Concatenated {
+                position,
+                separator_len,
+            } => {
+                // the start of text i is text_starts[i] + i * separator_len, which is also sorted
+                let start_of = |i: usize| self.text_starts[i] + i * separator_len;
+
+                let mut low = 0;
+                let mut high = self.text_starts.len();
+                while high - low > 1 {
+                    let mid = (low + high) / 2;
+                    if start_of(mid) <= position {
+                        low = mid;
+                    } else {
+                        high = mid;
+                    }
+                }
+
+                (low, position - start_of(low))
+            }
+        }
+    }
+
+    fn num_masked_symbols(&self) -> usize {
+        self.masked_intervals
+            .iter()
+            .flatten()
+            .map(|interval| interval.len())
+            .sum()
+    }
+}
@@ -1,77 +1,142 @@
 use std::marker::PhantomData;
 use std::path::Path;
 
-use crate::Config;
-use crate::common_interface::BenchmarkFmIndex;
-
-use sview_fmindex::blocks::Block3;
+use crate::common_interface::{
+    BenchmarkFmIndex, Capabilities, FileIoSupport, HitPosition, run_benchmark_for_text_size,
+};
+use crate::error::BenchmarkError;
+use crate::input::{Queries, Texts};
+use crate::registry::{LibraryInfo, Variant};
+use crate::{Alphabet, BenchmarkResult, Config};
+
+use sview_fmindex::blocks::{Block3, Block5};
 use sview_fmindex::build_config::{LookupTableConfig, SuffixArrayConfig};
 use sview_fmindex::text_encoders::EncodingTable;
-use sview_fmindex::{Position, blocks::Vector};
+use sview_fmindex::{Block, Position, blocks::Vector};
 
 // Based on [`lt-fm-index`], but improved memory usage during construction and after.
 // The `mmap` support is a nice idea, but probably only relevant for few applications.
-pub struct SViewFMIndex<P, V> {
+pub struct SViewFMIndex<P, B> {
     blob: Vec<u8>,
     _position_marker: PhantomData<P>,
-    _vector_marker: PhantomData<V>,
+    _block_marker: PhantomData<B>,
 }
 
-impl<P: Position + 'static, V: Vector + 'static> BenchmarkFmIndex for SViewFMIndex<P, V> {
-    type IndexRef<'a> = sview_fmindex::FmIndex<'a, P, Block3<V>, EncodingTable>;
+pub const LIBRARY: LibraryInfo = LibraryInfo {
+    crate_name: "sview-fmindex",
+    url: "https://github.com/baku4/sview-fmindex",
+    variants: &[
+        Variant {
+            name: "sview-fm-index-vec32",
+            display_name: "sview vec32",
+            color: "tomato",
+            color_with_threads: "tomato",
+            capabilities: SViewFMIndex::<u32, Block3<u32>>::CAPABILITIES,
+            run_benchmark: run_benchmark_for_alphabet::<u32>,
+        },
+        Variant {
+            name: "sview-fm-index-vec128",
+            display_name: "sview vec128",
+            color: "orange",
+            color_with_threads: "orange",
+            capabilities: SViewFMIndex::<u32, Block3<u128>>::CAPABILITIES,
+            run_benchmark: run_benchmark_for_alphabet::<u128>,
+        },
+    ],
+};
+
+// the number of bits per symbol of the blocks has to fit the alphabet size
+fn run_benchmark_for_alphabet<V: Vector + 'static>(
+    config: &Config,
+) -> Result<BenchmarkResult, BenchmarkError> {
+    match config.alphabet() {
+        Alphabet::Dna => run_benchmark_for_text_size::<
+            SViewFMIndex<u32, Block3<V>>,
+            SViewFMIndex<u32, Block3<V>>,
+            SViewFMIndex<u64, Block3<V>>,
+        >(config),
+        Alphabet::Protein => run_benchmark_for_text_size::<
+            SViewFMIndex<u32, Block5<V>>,
+            SViewFMIndex<u32, Block5<V>>,
+            SViewFMIndex<u64, Block5<V>>,
+        >(config),
+        Alphabet::Bytes => Err(BenchmarkError::Unsupported(String::from(
+            "sview-fmindex supports at most 64 symbols",
+        ))),
+    }
+}
 
-    fn construct_for_benchmark(config: &Config, texts: Option<Vec<Vec<u8>>>) -> Self {
-        let symbols: &[&[u8]] = &[b"A", b"C", b"G", b"T", b"N"];
-        let encoding_table = EncodingTable::from_symbols(symbols);
+impl<P: Position + 'static, B: Block + 'static> BenchmarkFmIndex for SViewFMIndex<P, B> {
+    type IndexRef<'a> = sview_fmindex::FmIndex<'a, P, B, EncodingTable>;
+    type Cursor<'a> = ();
+
+    const CAPABILITIES: Capabilities = Capabilities {
+        multiple_texts: false,
+        file_io: FileIoSupport::Fast,
+        file_io_mmap: true,
+        multithreaded_construction: false,
+        locate: true,
+        cursor: false,
+        reads_texts_from_file: false,
+        alphabets: &[Alphabet::Dna, Alphabet::Protein],
+    };
+
+    fn construct_for_benchmark(
+        config: &Config,
+        texts: Option<Texts>,
+    ) -> Result<Self, BenchmarkError> {
+        // the last symbol is used as the wildcard
+        let symbols: Vec<_> = config.alphabet().symbols().chunks(1).collect();
+        let encoding_table = EncodingTable::from_symbols(&symbols);
         let symbol_count = encoding_table.symbol_count();
 
-        let text: Vec<_> = texts.unwrap().into_iter().flatten().collect();
+        let text = texts.unwrap().into_symbols();
 
-        let builder = sview_fmindex::FmIndexBuilder::<P, Block3<V>, EncodingTable>::new(
+        let builder = sview_fmindex::FmIndexBuilder::<P, B, EncodingTable>::new(
             text.len(),
             symbol_count,
             encoding_table,
         )
-        .unwrap()
+        .map_err(BenchmarkError::construction)?
         .set_lookup_table_config(LookupTableConfig::KmerSize(
-            config.depth_of_lookup_table as u32,
+            config.lookup_table_depth() as u32
         ))
-        .unwrap()
+        .map_err(BenchmarkError::construction)?
         .set_suffix_array_config(SuffixArrayConfig::Compressed(
             config.suffix_array_sampling_rate as u32,
         ))
-        .unwrap();
+        .map_err(BenchmarkError::construction)?;
 
         let blob_size = builder.blob_size();
         let mut blob = vec![0; blob_size];
-        builder.build(text, &mut blob).unwrap();
+        builder
+            .build(text, &mut blob)
+            .map_err(BenchmarkError::construction)?;
 
-        SViewFMIndex {
+        Ok(SViewFMIndex {
             blob,
             _position_marker: PhantomData,
-            _vector_marker: PhantomData,
-        }
-    }
-
-    fn supports_file_io_for_benchmark(_config: &Config) -> bool {
-        true
+            _block_marker: PhantomData,
+        })
     }
 
-    fn write_to_file_for_benchmark(self, path: &Path) {
-        std::fs::write(path, self.blob).unwrap();
+    fn write_to_file_for_benchmark(self, path: &Path) -> Result<(), BenchmarkError> {
+        std::fs::write(path, self.blob).map_err(|e| BenchmarkError::io(path, e))
     }
 
-    fn load_from_file_for_benchmark(path: &Path) -> Self {
-        let blob = std::fs::read(path).unwrap();
-        SViewFMIndex {
+    fn load_from_file_for_benchmark(path: &Path) -> Result<Self, BenchmarkError> {
+        let blob = std::fs::read(path).map_err(|e| BenchmarkError::io(path, e))?;
+        Ok(SViewFMIndex {
             blob,
             _position_marker: PhantomData,
-            _vector_marker: PhantomData,
-        }
+            _block_marker: PhantomData,
+        })
     }
 
-    fn as_stub_for_benchmark<'a>(&'a self) -> Self::IndexRef<'a> {
-        Self::IndexRef::load(&self.blob).unwrap()
+    // this validates the blob, so a corrupted index file is detected here
+    fn as_stub_for_benchmark<'a>(&'a self) -> Result<Self::IndexRef<'a>, BenchmarkError> {
+        Self::IndexRef::load(&self.blob)
+            .map_err(|e| BenchmarkError::Construction(format!("invalid index blob: {e:?}")))
     }
 
     fn count_for_benchmark<'a>(index: &Self::IndexRef<'a>, query: &[u8]) -> usize {
@@ -82,14 +147,30 @@ impl<P: Position + 'static, V: Vector + 'static> BenchmarkFmIndex for SViewFMInd
         index.locate(query).len()
     }
 
+    fn locate_for_benchmark<'a>(
+        index: &Self::IndexRef<'a>,
+        query: &[u8],
+        hits: &mut Vec<HitPosition>,
+    ) {
+        hits.extend(
+            index
+                .locate(query)
+                .into_iter()
+                .map(|position| HitPosition::Concatenated {
+                    position: position.as_usize(),
+                    separator_len: 0,
+                }),
+        );
+    }
+
     fn count_many_via_locate_for_benchmark<'a>(
         index: &Self::IndexRef<'a>,
-        queries: &[Vec<u8>],
+        queries: &Queries,
     ) -> usize {
         let mut buffer = Vec::new();
         let mut sum = 0;
 
-        for query in queries {
+        for query in queries.iter() {
             index.locate_to_buffer(query, &mut buffer);
             sum += buffer.len();
             buffer.clear();